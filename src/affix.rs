@@ -0,0 +1,138 @@
+/// Characters stripped from either end of a term and kept around its occurrences,
+/// as in `_private`, `__init__`, `type_`, `$scope` or `@user`
+pub const AFFIX_CHARS: [char; 4] = ['_', '-', '$', '@'];
+
+/// One-letter prefix conventions used when no custom list is given:
+/// `IUserName` interfaces, `mUser` members and `kUserCount` constants
+pub const DEFAULT_PREFIXES: &[&str] = &["I", "m", "k"];
+
+/// Splits a prefix convention off `s` when one of `prefixes` is directly followed by
+/// a capitalised word, e.g. `I` and `UserName` for `IUserName`.
+/// A run of capitals is an acronym rather than a prefix, so `IPAddress` isn't split
+pub fn split_prefix<'s>(s: &'s str, prefixes: &[String]) -> Option<(&'s str, &'s str)> {
+    prefixes.iter().find_map(|prefix| {
+        let rest = s.strip_prefix(prefix.as_str())?;
+        let mut chars = rest.chars();
+
+        (chars.next().is_some_and(char::is_uppercase)
+            && chars.next().is_some_and(char::is_lowercase))
+        .then(|| (&s[..prefix.len()], rest))
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Affixes {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl Affixes {
+    /// Splits `s` into its affixes and the core between them
+    pub fn split(s: &str) -> (Affixes, &str) {
+        let core = s.trim_matches(AFFIX_CHARS);
+
+        if core.is_empty() {
+            return (Affixes::default(), s);
+        }
+
+        let start = s.len() - s.trim_start_matches(AFFIX_CHARS).len();
+        let end = s.trim_end_matches(AFFIX_CHARS).len();
+
+        let affixes = Affixes {
+            prefix: s[..start].to_string(),
+            suffix: s[end..].to_string(),
+        };
+
+        (affixes, core)
+    }
+
+    /// The affixes around `start..end` in `line`, provided they surround a standalone identifier,
+    /// e.g. `_user` in `(_user)` but not in `my_user`
+    pub fn around(line: &str, start: usize, end: usize) -> Option<Affixes> {
+        let before = &line[..start];
+        let after = &line[end..];
+
+        let prefix = &before[before.trim_end_matches(AFFIX_CHARS).len()..];
+        let suffix = &after[..after.len() - after.trim_start_matches(AFFIX_CHARS).len()];
+
+        let is_bounded = !before[..before.len() - prefix.len()]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric())
+            && !after[suffix.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric());
+
+        is_bounded.then(|| Affixes {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_empty() && self.suffix.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefix.len() + self.suffix.len()
+    }
+}
+
+#[cfg(test)]
+mod test_affix {
+    use super::{split_prefix, Affixes};
+
+    fn affixes(prefix: &str, suffix: &str) -> Affixes {
+        Affixes {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+        }
+    }
+
+    #[test]
+    fn split_terms() {
+        assert_eq!(Affixes::split("_user"), (affixes("_", ""), "user"));
+        assert_eq!(Affixes::split("__init__"), (affixes("__", "__"), "init"));
+        assert_eq!(Affixes::split("type_"), (affixes("", "_"), "type"));
+        assert_eq!(Affixes::split("$scope"), (affixes("$", ""), "scope"));
+        assert_eq!(
+            Affixes::split("@user_name"),
+            (affixes("@", ""), "user_name")
+        );
+        assert_eq!(Affixes::split("user"), (affixes("", ""), "user"));
+        assert_eq!(Affixes::split("__"), (affixes("", ""), "__"));
+    }
+
+    #[test]
+    fn prefix_conventions() {
+        let prefixes: Vec<String> = vec!["I".into(), "m".into(), "k".into()];
+
+        assert_eq!(
+            split_prefix("IUserName", &prefixes),
+            Some(("I", "UserName"))
+        );
+        assert_eq!(split_prefix("mUser", &prefixes), Some(("m", "User")));
+        assert_eq!(
+            split_prefix("kUserCount", &prefixes),
+            Some(("k", "UserCount"))
+        );
+        assert_eq!(split_prefix("IPAddress", &prefixes), None);
+        assert_eq!(split_prefix("IDValue", &prefixes), None);
+        assert_eq!(split_prefix("Image", &prefixes), None);
+        assert_eq!(split_prefix("mode", &prefixes), None);
+        assert_eq!(split_prefix("user", &prefixes), None);
+    }
+
+    #[test]
+    fn around_occurrences() {
+        assert_eq!(Affixes::around("(_user)", 2, 6), Some(affixes("_", "")));
+        assert_eq!(
+            Affixes::around("x = __init__()", 6, 10),
+            Some(affixes("__", "__"))
+        );
+        assert_eq!(Affixes::around("user", 0, 4), Some(affixes("", "")));
+        assert_eq!(Affixes::around("my_user", 3, 7), None);
+        assert_eq!(Affixes::around("_users", 1, 5), None);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::Result;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, clap::ValueEnum)]
 pub enum Casing {
     Camel,
     Pascal,
@@ -10,12 +10,74 @@ pub enum Casing {
     Upper,
     UpperSnake,
     UpperKebab,
+    Space,
+    Title,
+    Sentence,
+    Train,
+    Dot,
+    Path,
+    Ada,
 }
 
 impl Casing {
+    /// Every casing, in declaration order
+    pub const ALL: [Casing; 15] = [
+        Casing::Camel,
+        Casing::Pascal,
+        Casing::Lower,
+        Casing::Kebab,
+        Casing::Snake,
+        Casing::Upper,
+        Casing::UpperSnake,
+        Casing::UpperKebab,
+        Casing::Space,
+        Casing::Title,
+        Casing::Sentence,
+        Casing::Train,
+        Casing::Dot,
+        Casing::Path,
+        Casing::Ada,
+    ];
+
+    /// Casings searched by default
+    pub const IDENTIFIERS: [Casing; 8] = [
+        Casing::Lower,
+        Casing::Pascal,
+        Casing::Camel,
+        Casing::Snake,
+        Casing::Upper,
+        Casing::UpperSnake,
+        Casing::Kebab,
+        Casing::UpperKebab,
+    ];
+
+    /// Casings used in prose such as docs, comments and UI strings
+    pub const PROSE: [Casing; 3] = [Casing::Space, Casing::Title, Casing::Sentence];
+
+    /// The name the casing goes by on the command line, as in `upper-snake`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Casing::Camel => "camel",
+            Casing::Pascal => "pascal",
+            Casing::Lower => "lower",
+            Casing::Kebab => "kebab",
+            Casing::Snake => "snake",
+            Casing::Upper => "upper",
+            Casing::UpperSnake => "upper-snake",
+            Casing::UpperKebab => "upper-kebab",
+            Casing::Space => "space",
+            Casing::Title => "title",
+            Casing::Sentence => "sentence",
+            Casing::Train => "train",
+            Casing::Dot => "dot",
+            Casing::Path => "path",
+            Casing::Ada => "ada",
+        }
+    }
+
     pub fn detect_casing(s: &str) -> Result<Casing> {
         if s.chars().all(|c| c.is_numeric()) {
-            return Err(format!("All numeric values are not allowed").into());
+            return Err(String::from("All numeric values are not allowed").into());
         }
 
         if s.chars().all(|c| c.is_lowercase() || c.is_numeric()) {
@@ -52,12 +114,55 @@ impl Casing {
             return Ok(Casing::UpperKebab);
         }
 
+        let is_lower = |w: &str| w.chars().all(|c| c.is_lowercase() || c.is_numeric());
+        let is_capitalized = |w: &str| {
+            w.chars()
+                .next()
+                .is_some_and(|c| c.is_uppercase() || c.is_numeric())
+                && is_lower(&w[w.chars().next().map_or(0, |c| c.len_utf8())..])
+        };
+        let splits_into = |separator: char, is_word: &dyn Fn(&str) -> bool| {
+            s.contains(separator) && s.split(separator).all(|w| !w.is_empty() && is_word(w))
+        };
+
+        if splits_into('.', &is_lower) {
+            return Ok(Casing::Dot);
+        }
+
+        if splits_into('/', &is_lower) {
+            return Ok(Casing::Path);
+        }
+
+        if splits_into('-', &is_capitalized) {
+            return Ok(Casing::Train);
+        }
+
+        if splits_into('_', &is_capitalized) {
+            return Ok(Casing::Ada);
+        }
+
+        if s.contains(' ') && s.split(' ').all(|w| !w.is_empty()) {
+            if s.split(' ').all(is_lower) {
+                return Ok(Casing::Space);
+            }
+
+            if s.split(' ').all(is_capitalized) {
+                return Ok(Casing::Title);
+            }
+
+            if s.split(' ').next().is_some_and(is_capitalized) && s.split(' ').skip(1).all(is_lower)
+            {
+                return Ok(Casing::Sentence);
+            }
+        }
+
         // NOTE: PascalCase and CamelCase checks depend on position
         // which means the above checks are necessary and these cannot be moved arbitrarily
 
         if s.chars().next().is_some_and(|c| c.is_lowercase())
             && !s.contains('-')
             && !s.contains('_')
+            && !s.contains(' ')
         {
             return Ok(Casing::Camel);
         }
@@ -65,6 +170,7 @@ impl Casing {
         if s.chars().next().is_some_and(|c| c.is_uppercase())
             && !s.contains('-')
             && !s.contains('_')
+            && !s.contains(' ')
         {
             return Ok(Casing::Pascal);
         }
@@ -149,12 +255,96 @@ mod test_casing {
         assert_inputs_casing(&inputs, Casing::UpperKebab);
     }
 
+    #[test]
+    fn space_case() {
+        let inputs = vec![
+            "space case",
+            "with spaces",
+            "multiple words here",
+            "num 123",
+        ];
+
+        assert_inputs_casing(&inputs, Casing::Space);
+    }
+
+    #[test]
+    fn title_case() {
+        let inputs = vec![
+            "Title Case",
+            "With Spaces",
+            "Multiple Words Here",
+            "Num 123",
+        ];
+
+        assert_inputs_casing(&inputs, Casing::Title);
+    }
+
+    #[test]
+    fn sentence_case() {
+        let inputs = vec![
+            "Sentence case",
+            "With spaces",
+            "Multiple words here",
+            "Num 123 items",
+        ];
+
+        assert_inputs_casing(&inputs, Casing::Sentence);
+    }
+
+    #[test]
+    fn train_case() {
+        let inputs = vec!["Train-Case", "Content-Type", "Multiple-Words", "Num-123"];
+
+        assert_inputs_casing(&inputs, Casing::Train);
+    }
+
+    #[test]
+    fn dot_case() {
+        let inputs = vec!["dot.case", "com.example", "multiple.words", "num.123"];
+
+        assert_inputs_casing(&inputs, Casing::Dot);
+    }
+
+    #[test]
+    fn path_case() {
+        let inputs = vec!["path/case", "api/users", "multiple/words", "num/123"];
+
+        assert_inputs_casing(&inputs, Casing::Path);
+    }
+
+    #[test]
+    fn ada_case() {
+        let inputs = vec!["Ada_Case", "User_Name", "Multiple_Words", "Num_123"];
+
+        assert_inputs_casing(&inputs, Casing::Ada);
+    }
+
     #[test]
     fn invalid_case() {
-        let inputs = vec!["InVA-lid", "INVa_lid", "in-Va_lid", "in-va_lid", "123"];
+        let inputs = [
+            "InVA-lid",
+            "INVa_lid",
+            "in-Va_lid",
+            "in-va_lid",
+            "123",
+            "in Valid",
+            "in  valid",
+            "/path",
+            "Ada_case",
+        ];
 
         assert!(inputs.iter().all(|i| Casing::detect_casing(i).is_err()));
     }
+
+    #[test]
+    fn names() {
+        use clap::ValueEnum;
+
+        assert_eq!(Casing::ALL.as_slice(), Casing::value_variants());
+        assert!(Casing::ALL
+            .iter()
+            .all(|c| c.to_possible_value().unwrap().get_name() == c.name()));
+    }
 }
 
 #[derive(Debug)]
@@ -162,6 +352,9 @@ pub enum CasingSeparator {
     None,
     Underscore,
     Hyphen,
+    Space,
+    Dot,
+    Slash,
 }
 
 impl From<Casing> for CasingSeparator {
@@ -175,6 +368,13 @@ impl From<Casing> for CasingSeparator {
             Casing::Upper => CasingSeparator::None,
             Casing::UpperSnake => CasingSeparator::Underscore,
             Casing::UpperKebab => CasingSeparator::Hyphen,
+            Casing::Space => CasingSeparator::Space,
+            Casing::Title => CasingSeparator::Space,
+            Casing::Sentence => CasingSeparator::Space,
+            Casing::Train => CasingSeparator::Hyphen,
+            Casing::Dot => CasingSeparator::Dot,
+            Casing::Path => CasingSeparator::Slash,
+            Casing::Ada => CasingSeparator::Underscore,
         }
     }
 }
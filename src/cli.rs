@@ -1,23 +1,453 @@
 use std::{
     fs::{File, OpenOptions},
     io::{self, BufRead, BufReader, Read, Write},
+    num::NonZeroUsize,
+    path::Path,
 };
 
-use altr::task::Task;
-use altr::Result;
+use altr::affix::DEFAULT_PREFIXES;
+use altr::casing::Casing;
+use altr::dictionary::Dictionary;
+use altr::filter::Filter;
+use altr::lang::{InvalidRename, Lang, OnInvalid};
+use altr::lexer::{Region, Syntax};
+use altr::markdown::MarkdownScope;
+use altr::structured::{Format, Position};
+use altr::task::{LowerAs, Occurrence, Options, Resolution, Task, UpperAs, CANDIDATE_SEPARATOR};
+use altr::token::{DigitPolicy, DEFAULT_ACRONYMS};
+use altr::{Error, Result};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 #[derive(Parser, Debug)]
 struct Cli {
+    /// Term to rename, several spellings can be separated by `|` (user|usr).
+    /// Taken as the rename when --candidate is given with a single term
     candidate: String,
-    rename: String,
+    rename: Option<String>,
+
+    /// Another spelling of the candidate renamed in the same pass, can be repeated
+    #[arg(long = "candidate", value_name = "CANDIDATE")]
+    candidates: Vec<String>,
 
     #[arg(short = 'f', long, default_value = "-")]
     input: String,
 
     #[arg(short, long)]
     output: Option<String>,
+
+    /// Render acronyms in all caps in camelCase and PascalCase renames (e.g. accountID).
+    /// Takes an optional comma separated list, otherwise a built-in list is used
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ',',
+        value_name = "LIST"
+    )]
+    acronyms: Option<Vec<String>>,
+
+    /// How digits are grouped into words when parsing the terms
+    #[arg(long, value_enum, default_value_t = DigitPolicy::AsWritten)]
+    digits: DigitPolicy,
+
+    /// Also match prose casings: "space case", "Title Case" and "Sentence case"
+    #[arg(long)]
+    prose: bool,
+
+    /// Also search the given casings, e.g. train,dot,path,ada
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CASINGS")]
+    add_casing: Vec<Casing>,
+
+    /// Only search the given casings, e.g. upper-snake,upper-kebab
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CASINGS")]
+    only_casing: Vec<Casing>,
+
+    /// Don't search the given casings, e.g. snake,kebab
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "CASINGS")]
+    skip_casing: Vec<Casing>,
+
+    /// Also match the flatcase and UPPERCASE forms of multi-word terms (myuser, MYUSER)
+    #[arg(long)]
+    flat: bool,
+
+    /// Word list used by --flat to split flat terms into words, one word per line
+    #[arg(long, value_name = "FILE", requires = "flat")]
+    words: Option<String>,
+
+    /// Form used when a multi-word rename replaces a single-word lowercase match
+    #[arg(long, value_enum)]
+    lower_as: Option<LowerAs>,
+
+    /// Form used when a multi-word rename replaces a single-word UPPERCASE match
+    #[arg(long, value_enum)]
+    upper_as: Option<UpperAs>,
+
+    /// How ambiguous matches are resolved when --lower-as or --upper-as is not given
+    #[arg(long, value_enum, default_value_t = Resolution::Rename)]
+    resolve_ambiguity: Resolution,
+
+    /// Use a literal rename for matches of the given casing, e.g. upper-snake=PTX
+    #[arg(long = "as", value_name = "CASING=RENAME", value_parser = parse_override)]
+    overrides: Vec<(Casing, String)>,
+
+    /// Report occurrences that match case-insensitively but are left alone
+    #[arg(long)]
+    report_skipped: bool,
+
+    /// Also rename occurrences with mixed or unrecognised casing, mapping their character cases
+    /// onto the rename (rEsult -> pArsedtransaction)
+    #[arg(long)]
+    force_mixed: bool,
+
+    /// Recognise one-letter prefix conventions such as IUser, mUser and kUserCount.
+    /// Takes an optional comma separated list, otherwise I,m,k is used
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ',',
+        value_name = "LIST"
+    )]
+    prefixes: Option<Vec<String>>,
+
+    /// Also rename plural forms (people -> customers for person -> customer)
+    #[arg(long)]
+    plural: bool,
+
+    /// Add an irregular plural, e.g. cactus=cacti
+    #[arg(long, value_name = "SINGULAR=PLURAL", value_parser = parse_irregular, requires = "plural")]
+    irregular: Vec<(String, String)>,
+
+    /// Also fix up plural possessives (people's -> customers')
+    #[arg(long, requires = "plural")]
+    possessive: bool,
+
+    /// Treat the candidate as a regex matched against whole identifiers in any casing.
+    /// Groups are referenced in the rename as {1}, {2}... e.g. 'user(\w+)Dto' 'account{1}Model'
+    #[arg(long, conflicts_with = "candidates")]
+    regex: bool,
+
+    /// Rename even when the rename term already occurs in the input
+    #[arg(long)]
+    allow_collisions: bool,
+
+    /// Check renamed identifiers against the keywords and identifier syntax of a language,
+    /// inferred from the input file extension when not given
+    #[arg(long, value_enum)]
+    lang: Option<Lang>,
+
+    /// What to do with renames that produce a keyword or an invalid identifier
+    #[arg(long, value_enum, default_value_t = OnInvalid::Warn)]
+    on_invalid: OnInvalid,
+
+    /// Escape renames that produce a Rust keyword as raw identifiers (r#type)
+    #[arg(long)]
+    raw_identifiers: bool,
+
+    /// Only rename occurrences in code, comments or strings
+    #[arg(long, value_enum)]
+    only: Option<Region>,
+
+    /// Comment and string syntax used by --only, inferred from --lang or the input file
+    /// extension when not given
+    #[arg(long, value_enum)]
+    syntax: Option<Syntax>,
+
+    /// Only rename occurrences in the code (fenced blocks and inline spans) or in the prose
+    /// of a Markdown input
+    #[arg(long, value_enum)]
+    markdown_scope: Option<MarkdownScope>,
+
+    /// Also match prose casings (Title Case, Sentence case) in the prose of a Markdown input
+    #[arg(long)]
+    markdown_prose: bool,
+
+    /// Only rename the keys of a JSON, YAML or TOML input
+    #[arg(long)]
+    keys: bool,
+
+    /// Only rename the values of a JSON, YAML or TOML input, together with the keys when
+    /// --keys is given too
+    #[arg(long)]
+    values: bool,
+
+    /// Format of the data file for --keys and --values, inferred from the input file
+    /// extension when not given
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    /// Ignore the altr:ignore-line, altr:ignore-next-line and altr:off/altr:on directives
+    #[arg(long)]
+    no_directives: bool,
+
+    /// Only rename occurrences on lines START:END (1-based, inclusive, either end optional).
+    /// A single N is line N. Can be repeated
+    #[arg(long = "lines", value_name = "START:END", value_parser = parse_span)]
+    lines: Vec<(Option<usize>, Option<usize>)>,
+
+    /// Only rename occurrences within bytes START:END (end exclusive, either end optional).
+    /// The colon is required. Can be repeated
+    #[arg(long = "range", value_name = "START:END", value_parser = parse_byte_span)]
+    ranges: Vec<(Option<usize>, Option<usize>)>,
+
+    /// Only rename occurrences between altr:begin and altr:end markers
+    #[arg(long)]
+    marked: bool,
+
+    /// Only rename occurrences on lines matching REGEX. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    where_line: Vec<String>,
+
+    /// Don't rename occurrences on lines matching REGEX. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    skip_line: Vec<String>,
+
+    /// Only rename occurrences directly preceded by REGEX on their line. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    before: Vec<String>,
+
+    /// Don't rename occurrences directly preceded by REGEX, e.g. 'legacy\.'. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    skip_before: Vec<String>,
+
+    /// Only rename occurrences directly followed by REGEX on their line. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    after: Vec<String>,
+
+    /// Don't rename occurrences directly followed by REGEX. Can be repeated
+    #[arg(long, value_name = "REGEX")]
+    skip_after: Vec<String>,
+
+    /// Only rename the Nth occurrence, counting from 1
+    #[arg(long, value_name = "N", conflicts_with_all = ["first", "last"])]
+    nth: Option<NonZeroUsize>,
+
+    /// Only rename the first occurrence
+    #[arg(long, conflicts_with = "last")]
+    first: bool,
+
+    /// Only rename the last occurrence
+    #[arg(long)]
+    last: bool,
+
+    /// Leave the first N occurrences alone
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    skip: usize,
+
+    /// Rename at most N occurrences. altr renames a single input per run, so this is both
+    /// the per-file and the global limit
+    #[arg(long, value_name = "N")]
+    max_count: Option<usize>,
+}
+
+fn parse_span(s: &str) -> std::result::Result<(Option<usize>, Option<usize>), String> {
+    let bound = |b: &str| -> std::result::Result<Option<usize>, String> {
+        match b {
+            "" => Ok(None),
+            b => b
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid number `{b}`")),
+        }
+    };
+
+    let span = match s.split_once(':') {
+        Some((start, end)) => (bound(start)?, bound(end)?),
+        None => (bound(s)?, bound(s)?),
+    };
+
+    match span {
+        (Some(start), Some(end)) if start > end => Err(format!("`{s}` ends before it starts")),
+        span => Ok(span),
+    }
+}
+
+/// A byte span, which unlike a line span can't be a single number since `N..N` is empty
+fn parse_byte_span(s: &str) -> std::result::Result<(Option<usize>, Option<usize>), String> {
+    if !s.contains(':') {
+        return Err(format!(
+            "expected START:END, found `{s}` (use `{s}:` to run to the end)"
+        ));
+    }
+
+    parse_span(s)
+}
+
+fn parse_irregular(s: &str) -> std::result::Result<(String, String), String> {
+    let (singular, plural) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected SINGULAR=PLURAL, found `{s}`"))?;
+
+    Ok((singular.to_lowercase(), plural.to_lowercase()))
+}
+
+fn parse_override(s: &str) -> std::result::Result<(Casing, String), String> {
+    let (casing, rename) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected CASING=RENAME, found `{s}`"))?;
+
+    Ok((Casing::from_str(casing, true)?, rename.to_string()))
+}
+
+impl Cli {
+    /// The data file format and positions selected with --keys and --values
+    fn structured(&self) -> Result<(Option<Format>, Vec<Position>)> {
+        let mut positions = vec![];
+
+        if self.keys {
+            positions.push(Position::Key);
+        }
+        if self.values {
+            positions.push(Position::Value);
+        }
+
+        if positions.is_empty() {
+            return Ok((None, positions));
+        }
+
+        let format = self.format.or_else(|| {
+            Path::new(&self.input)
+                .extension()
+                .and_then(|ext| Format::from_extension(ext.to_str()?))
+        });
+
+        match format {
+            Some(format) => Ok((Some(format), positions)),
+            None => Err(String::from("Failed to infer the data format, use --format").into()),
+        }
+    }
+
+    /// The context filters given by --where-line, --skip-line, --before and --after
+    fn filters(&self) -> Result<Vec<Filter>> {
+        let mut filters = vec![];
+
+        for pattern in self.where_line.iter() {
+            filters.push(Filter::where_line(pattern)?);
+        }
+        for pattern in self.skip_line.iter() {
+            filters.push(Filter::skip_line(pattern)?);
+        }
+        for pattern in self.before.iter() {
+            filters.push(Filter::before(pattern)?);
+        }
+        for pattern in self.skip_before.iter() {
+            filters.push(Filter::skip_before(pattern)?);
+        }
+        for pattern in self.after.iter() {
+            filters.push(Filter::after(pattern)?);
+        }
+        for pattern in self.skip_after.iter() {
+            filters.push(Filter::skip_after(pattern)?);
+        }
+
+        Ok(filters)
+    }
+
+    /// The candidate term with all its spellings joined, and the rename term
+    fn terms(&self) -> Result<(String, String)> {
+        let mut candidates = self.candidates.clone();
+
+        let rename = match &self.rename {
+            Some(rename) => {
+                candidates.insert(0, self.candidate.clone());
+                rename.clone()
+            }
+            None if !candidates.is_empty() => self.candidate.clone(),
+            None => return Err(String::from("Missing the rename term").into()),
+        };
+
+        Ok((candidates.join(&String::from(CANDIDATE_SEPARATOR)), rename))
+    }
+
+    fn options(&self) -> Result<Options> {
+        let acronyms = match &self.acronyms {
+            Some(list) if list.is_empty() => {
+                DEFAULT_ACRONYMS.iter().map(|a| a.to_string()).collect()
+            }
+            Some(list) => list.clone(),
+            None => vec![],
+        };
+
+        let prefixes = match &self.prefixes {
+            Some(list) if list.is_empty() => {
+                DEFAULT_PREFIXES.iter().map(|p| p.to_string()).collect()
+            }
+            Some(list) => list.clone(),
+            None => vec![],
+        };
+
+        let dictionary = match &self.words {
+            Some(path) => {
+                let mut words = String::new();
+                get_file_reader(path)?.read_to_string(&mut words)?;
+                Some(Dictionary::from_words(words.lines()))
+            }
+            None => None,
+        };
+
+        let (structured, positions) = self.structured()?;
+
+        Ok(Options {
+            acronyms,
+            digits: self.digits,
+            prose: self.prose,
+            extra_casings: self.add_casing.clone(),
+            only_casings: (!self.only_casing.is_empty()).then(|| self.only_casing.clone()),
+            skip_casings: self.skip_casing.clone(),
+            flat: self.flat,
+            dictionary,
+            lower_as: self.lower_as,
+            upper_as: self.upper_as,
+            resolve_ambiguity: self.resolve_ambiguity,
+            overrides: self.overrides.clone(),
+            force_mixed: self.force_mixed,
+            prefixes,
+            plural: self.plural,
+            irregulars: self.irregular.clone(),
+            possessive: self.possessive,
+            regex: self.regex,
+            allow_collisions: self.allow_collisions,
+            lang: self.lang.or_else(|| {
+                Path::new(&self.input)
+                    .extension()
+                    .and_then(|ext| Lang::from_extension(ext.to_str()?))
+            }),
+            on_invalid: self.on_invalid,
+            raw_identifiers: self.raw_identifiers,
+            syntax: self.syntax.or_else(|| {
+                Path::new(&self.input)
+                    .extension()
+                    .and_then(|ext| Syntax::from_extension(ext.to_str()?))
+            }),
+            only: self.only,
+            markdown_scope: self.markdown_scope,
+            markdown_prose: self.markdown_prose,
+            structured,
+            positions,
+            no_directives: self.no_directives,
+            lines: self
+                .lines
+                .iter()
+                .map(|(start, end)| start.unwrap_or(1)..=end.unwrap_or(usize::MAX))
+                .collect(),
+            byte_ranges: self
+                .ranges
+                .iter()
+                .map(|(start, end)| start.unwrap_or(0)..end.unwrap_or(usize::MAX))
+                .collect(),
+            marked: self.marked,
+            filters: self.filters()?,
+            skip: self.skip,
+            occurrence: match (self.nth, self.first, self.last) {
+                (Some(n), _, _) => Occurrence::Nth(n),
+                (_, true, _) => Occurrence::Nth(NonZeroUsize::MIN),
+                (_, _, true) => Occurrence::Last,
+                _ => Occurrence::All,
+            },
+            max_count: self.max_count,
+        })
+    }
 }
 
 fn get_file_reader(path: &str) -> Result<impl BufRead> {
@@ -40,9 +470,49 @@ pub fn run() -> Result<()> {
         _ => get_file_reader(&cli.input)?.read_to_string(&mut buf),
     };
 
-    let mut task = Task::build(&cli.candidate, &cli.rename, &buf)?;
+    let (candidate, rename) = cli.terms()?;
+    let mut task = Task::build_with_options(&candidate, &rename, &buf, cli.options()?)?;
 
     let mut records = task.generate_records();
+
+    if cli.report_skipped {
+        for skipped in task.skipped(&records) {
+            let text = &buf[skipped.pos..skipped.pos + skipped.len];
+            eprintln!(
+                "{}: skipped `{}`: {}",
+                skipped.location, text, skipped.reason
+            );
+        }
+    }
+
+    if let Err(Error::Collisions(collisions)) = task.check_collisions(&records) {
+        for collision in collisions.iter() {
+            let text = &buf[collision.pos..collision.pos + collision.len];
+            eprintln!("{}: `{}` already exists", collision.location, text);
+        }
+
+        eprintln!("Use --allow-collisions to rename anyway");
+        return Err(Error::Collisions(collisions));
+    }
+
+    let report_invalid = |invalid: &[InvalidRename]| {
+        for rename in invalid {
+            eprintln!(
+                "{}: `{}` {}",
+                rename.location, rename.identifier, rename.problem
+            );
+        }
+    };
+
+    match task.check_renames(&records) {
+        Ok(invalid) => report_invalid(&invalid),
+        Err(Error::InvalidRenames(invalid)) => {
+            report_invalid(&invalid);
+            return Err(Error::InvalidRenames(invalid));
+        }
+        Err(e) => return Err(e),
+    }
+
     let (processed_buf, _) = task.process_records(&mut records);
 
     let output = cli.output.unwrap_or(cli.input);
@@ -0,0 +1,44 @@
+use crate::location::Location;
+
+/// An occurrence of the rename term, in any casing, that already exists in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub pos: usize,
+    pub len: usize,
+    pub location: Location,
+}
+
+/// Whether `haystack[pos..pos + len]` stands on its own rather than inside a longer word,
+/// e.g. `account` in `account_id` or `Account` in `AccountService` but not `account` in `accountant`
+/// or `ACCOUNT` in `ACCOUNTANT`
+pub fn is_word_bounded(haystack: &str, pos: usize, len: usize) -> bool {
+    let text = &haystack[pos..pos + len];
+    let starts_lower = text.chars().next().is_some_and(char::is_lowercase);
+    let all_caps = !text.chars().any(char::is_lowercase);
+    let before = haystack[..pos].chars().next_back();
+    let after = haystack[pos + len..].chars().next();
+
+    // NOTE: in all caps another capital carries on the same word
+    let continues_word = after.is_some_and(|c| c.is_lowercase() || (all_caps && c.is_uppercase()));
+    let extends_word = before
+        .is_some_and(|c| (starts_lower && c.is_alphabetic()) || (all_caps && c.is_uppercase()));
+
+    !(continues_word || extends_word)
+}
+
+#[cfg(test)]
+mod test_collision {
+    use super::is_word_bounded;
+
+    #[test]
+    fn word_bounds() {
+        let haystack = "account_id AccountService accountant myaccount ACCOUNT ACCOUNTANT";
+
+        assert!(is_word_bounded(haystack, 0, 7));
+        assert!(is_word_bounded(haystack, 11, 7));
+        assert!(!is_word_bounded(haystack, 26, 7));
+        assert!(!is_word_bounded(haystack, 39, 7));
+        assert!(is_word_bounded(haystack, 47, 7));
+        assert!(!is_word_bounded(haystack, 55, 7));
+    }
+}
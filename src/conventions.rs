@@ -0,0 +1,82 @@
+use crate::casing::Casing;
+
+/// Tally of the multi-word identifier casings used in a piece of text
+#[derive(Debug, Default)]
+pub struct Conventions {
+    counts: Vec<(Casing, usize)>,
+}
+
+impl Conventions {
+    pub fn scan(text: &str) -> Self {
+        let mut conventions = Conventions::default();
+
+        let identifiers = text
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .map(|word| word.trim_matches(|c| c == '_' || c == '-'))
+            .filter(|word| !word.is_empty());
+
+        for identifier in identifiers {
+            if let Ok(casing) = Casing::detect_casing(identifier) {
+                conventions.add(casing);
+            }
+        }
+
+        conventions
+    }
+
+    fn add(&mut self, casing: Casing) {
+        match self.counts.iter_mut().find(|(c, _)| *c == casing) {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((casing, 1)),
+        }
+    }
+
+    fn count(&self, casing: &Casing) -> usize {
+        self.counts
+            .iter()
+            .find(|(c, _)| c == casing)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// The most used of `casings`, if any was used and it isn't tied with another
+    fn dominant(&self, casings: &[Casing]) -> Option<Casing> {
+        let mut ranked: Vec<_> = casings.iter().map(|c| (c, self.count(c))).collect();
+        ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        match ranked.as_slice() {
+            [(casing, count), rest @ ..] if *count > 0 && rest.iter().all(|(_, n)| n < count) => {
+                Some((*casing).clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// The dominant style among identifiers that could stand in for a lowercase word
+    pub fn dominant_lower(&self) -> Option<Casing> {
+        self.dominant(&[Casing::Snake, Casing::Camel, Casing::Kebab])
+    }
+
+    /// The dominant style among identifiers that could stand in for an UPPERCASE word
+    pub fn dominant_upper(&self) -> Option<Casing> {
+        self.dominant(&[Casing::UpperSnake, Casing::UpperKebab])
+    }
+}
+
+#[cfg(test)]
+mod test_conventions {
+    use super::Conventions;
+    use crate::casing::Casing;
+
+    #[test]
+    fn dominant_styles() {
+        let conventions = Conventions::scan("let user_name = get_user(user_id, maxCount);");
+        assert_eq!(conventions.dominant_lower(), Some(Casing::Snake));
+
+        let conventions = Conventions::scan("const userName = getUser(MAX_COUNT, API-KEY);");
+        assert_eq!(conventions.dominant_lower(), Some(Casing::Camel));
+        assert_eq!(conventions.dominant_upper(), None);
+
+        let conventions = Conventions::scan("user_name userName");
+        assert_eq!(conventions.dominant_lower(), None);
+    }
+}
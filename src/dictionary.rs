@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+const BUNDLED_WORDS: &str = include_str!("dictionary/words.txt");
+
+/// Word list used to split flatcase and UPPERCASE identifiers into words
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    pub fn bundled() -> Self {
+        Dictionary::from_words(BUNDLED_WORDS.lines())
+    }
+
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().trim().to_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Splits `input` into the fewest dictionary words that cover it entirely.
+    /// Runs of digits are accepted as words of their own.
+    ///
+    /// Returns `None` when no such split exists
+    pub fn segment(&self, input: &str) -> Option<Vec<String>> {
+        let input = input.to_lowercase();
+        let bounds: Vec<usize> = input
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(input.len()))
+            .collect();
+
+        // best[i] holds the shortest segmentation of the first i characters
+        let mut best: Vec<Option<Vec<&str>>> = vec![None; bounds.len()];
+        best[0] = Some(vec![]);
+
+        for end in 1..bounds.len() {
+            for start in 0..end {
+                let Some(prefix) = &best[start] else {
+                    continue;
+                };
+
+                let word = &input[bounds[start]..bounds[end]];
+                let is_word = self.words.contains(word) || word.chars().all(|c| c.is_numeric());
+                let is_shorter = match &best[end] {
+                    Some(b) => prefix.len() + 1 < b.len(),
+                    None => true,
+                };
+
+                if is_word && is_shorter {
+                    let mut segmentation = prefix.clone();
+                    segmentation.push(word);
+                    best[end] = Some(segmentation);
+                }
+            }
+        }
+
+        best.pop()
+            .flatten()
+            .map(|words| words.into_iter().map(String::from).collect())
+    }
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Dictionary::bundled()
+    }
+}
+
+#[cfg(test)]
+mod test_dictionary {
+    use super::Dictionary;
+
+    #[test]
+    fn bundled_segmentation() {
+        let dictionary = Dictionary::bundled();
+
+        assert_eq!(dictionary.segment("myuser").unwrap(), vec!["my", "user"]);
+        assert_eq!(
+            dictionary.segment("MYNEWUSER").unwrap(),
+            vec!["my", "new", "user"]
+        );
+        assert_eq!(
+            dictionary.segment("userid2").unwrap(),
+            vec!["user", "id", "2"]
+        );
+        assert!(dictionary.segment("xyzzy").is_none());
+    }
+
+    #[test]
+    fn custom_words() {
+        let dictionary = Dictionary::from_words(["supply", "chain"]);
+
+        assert_eq!(
+            dictionary.segment("supplychain").unwrap(),
+            vec!["supply", "chain"]
+        );
+        assert!(dictionary.segment("myuser").is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use std::ops::Range;
+
+/// Marks the lines renames are left out of, in any comment syntax
+const MARKER: &str = "altr:";
+
+/// Tokens that open a comment, one of which must come right before a marker
+const COMMENT_LEADERS: [&str; 5] = ["//", "#", "--", "/*", "<!--"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// `altr:ignore-line`, the line the directive is on
+    IgnoreLine,
+    /// `altr:ignore-next-line`
+    IgnoreNextLine,
+    /// `altr:off`, up to the next `altr:on`
+    Off,
+    /// `altr:on`
+    On,
+    /// `altr:begin`, starts a region renamed with `--marked`
+    Begin,
+    /// `altr:end`
+    End,
+}
+
+impl Directive {
+    /// The directives on `line`, which must each start a comment so that markers quoted in
+    /// strings, as in `x = "altr:off"`, don't count
+    pub fn parse(line: &str) -> Vec<Directive> {
+        line.match_indices(MARKER)
+            .filter(|(i, _)| {
+                let before = line[..*i].trim_end();
+                COMMENT_LEADERS
+                    .iter()
+                    .any(|leader| before.ends_with(leader))
+            })
+            .filter_map(|(i, _)| {
+                let rest = &line[i + MARKER.len()..];
+                let name = &rest[..rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                    .unwrap_or(rest.len())];
+
+                match name {
+                    "ignore-line" => Some(Directive::IgnoreLine),
+                    "ignore-next-line" => Some(Directive::IgnoreNextLine),
+                    "off" => Some(Directive::Off),
+                    "on" => Some(Directive::On),
+                    "begin" => Some(Directive::Begin),
+                    "end" => Some(Directive::End),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The byte ranges of the lines of `buf` that directives leave out of renames, in order
+pub fn ignored_lines(buf: &str) -> Vec<Range<usize>> {
+    let mut ignored: Vec<Range<usize>> = vec![];
+    let mut off = false;
+    let mut ignore_next = false;
+    let mut line_start = 0;
+
+    for line in buf.split_inclusive('\n') {
+        let range = line_start..line_start + line.len();
+        line_start = range.end;
+
+        let directives = Directive::parse(line);
+        let mut is_ignored = off || ignore_next;
+        ignore_next = false;
+
+        for directive in directives {
+            match directive {
+                Directive::IgnoreLine => is_ignored = true,
+                Directive::IgnoreNextLine => ignore_next = true,
+                Directive::Off => {
+                    off = true;
+                    is_ignored = true;
+                }
+                Directive::On => off = false,
+                Directive::Begin | Directive::End => {}
+            }
+        }
+
+        if !is_ignored {
+            continue;
+        }
+
+        match ignored.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ignored.push(range),
+        }
+    }
+
+    ignored
+}
+
+/// The byte ranges of the lines of `buf` between `altr:begin` and `altr:end` markers,
+/// the marker lines left out
+pub fn marked_lines(buf: &str) -> Vec<Range<usize>> {
+    let mut marked: Vec<Range<usize>> = vec![];
+    let mut start = None;
+    let mut line_start = 0;
+
+    for line in buf.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+
+        for directive in Directive::parse(line) {
+            match directive {
+                Directive::Begin => start = start.or(Some(line_end)),
+                Directive::End => {
+                    if let Some(start) = start.take() {
+                        marked.push(start..line_start.max(start));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        line_start = line_end;
+    }
+
+    if let Some(start) = start {
+        marked.push(start..buf.len());
+    }
+
+    marked
+}
+
+#[cfg(test)]
+mod test_directive {
+    use super::{ignored_lines, marked_lines, Directive};
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            Directive::parse("let a = 1; // altr:ignore-line"),
+            vec![Directive::IgnoreLine]
+        );
+        assert_eq!(
+            Directive::parse("# altr:ignore-next-line"),
+            vec![Directive::IgnoreNextLine]
+        );
+        assert_eq!(Directive::parse("<!-- altr:off -->"), vec![Directive::Off]);
+        assert_eq!(Directive::parse("-- altr:onward"), vec![]);
+        assert_eq!(Directive::parse("x = \"altr:off\""), vec![]);
+        assert_eq!(Directive::parse("// note altr:off"), vec![]);
+    }
+
+    #[test]
+    fn marked_ranges() {
+        let buf = "a\n# altr:begin\nb\nc\n# altr:end\nd\n# altr:begin\ne";
+        let lines: Vec<&str> = marked_lines(buf).into_iter().map(|r| &buf[r]).collect();
+
+        assert_eq!(lines, vec!["b\nc\n", "e"]);
+    }
+
+    #[test]
+    fn ignored_ranges() {
+        let buf = "a\nb // altr:ignore-line\n// altr:ignore-next-line\nc\nd\n/* altr:off */\ne\n/* altr:on */\nf";
+        let lines: Vec<&str> = ignored_lines(buf).into_iter().map(|r| &buf[r]).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "b // altr:ignore-line\n",
+                "c\n",
+                "/* altr:off */\ne\n/* altr:on */\n"
+            ]
+        );
+    }
+}
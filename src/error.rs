@@ -1,5 +1,7 @@
 use derive_more::{Display, From};
 
+use crate::{collision::Collision, lang::InvalidRename};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, From, Display)]
@@ -23,6 +25,18 @@ pub enum Error {
     )]
     Custom(String),
 
+    #[display(
+        fmt = "Found {} existing occurrence(s) of the rename term, renaming would merge them with the candidate",
+        "_0.len()"
+    )]
+    Collisions(Vec<Collision>),
+
+    #[display(
+        fmt = "{} rename(s) would produce a keyword or an invalid identifier",
+        "_0.len()"
+    )]
+    InvalidRenames(Vec<InvalidRename>),
+
     #[from]
     #[display(fmt = "{}", "_0")]
     Io(std::io::Error),
@@ -0,0 +1,97 @@
+use regex::Regex;
+
+use crate::Result;
+
+/// A condition on the text around an occurrence for it to be renamed
+#[derive(Debug, Clone)]
+pub enum Filter {
+    /// The occurrence's line matches
+    WhereLine(Regex),
+    /// The occurrence's line doesn't match
+    SkipLine(Regex),
+    /// The text before the occurrence on its line ends with a match
+    Before(Regex),
+    /// The text before the occurrence on its line doesn't end with a match
+    SkipBefore(Regex),
+    /// The text after the occurrence on its line starts with a match
+    After(Regex),
+    /// The text after the occurrence on its line doesn't start with a match
+    SkipAfter(Regex),
+}
+
+impl Filter {
+    pub fn where_line(pattern: &str) -> Result<Self> {
+        Ok(Filter::WhereLine(compile(pattern, pattern)?))
+    }
+
+    pub fn skip_line(pattern: &str) -> Result<Self> {
+        Ok(Filter::SkipLine(compile(pattern, pattern)?))
+    }
+
+    pub fn before(pattern: &str) -> Result<Self> {
+        Ok(Filter::Before(compile(
+            &format!("(?:{pattern})$"),
+            pattern,
+        )?))
+    }
+
+    pub fn skip_before(pattern: &str) -> Result<Self> {
+        Ok(Filter::SkipBefore(compile(
+            &format!("(?:{pattern})$"),
+            pattern,
+        )?))
+    }
+
+    pub fn after(pattern: &str) -> Result<Self> {
+        Ok(Filter::After(compile(&format!("^(?:{pattern})"), pattern)?))
+    }
+
+    pub fn skip_after(pattern: &str) -> Result<Self> {
+        Ok(Filter::SkipAfter(compile(
+            &format!("^(?:{pattern})"),
+            pattern,
+        )?))
+    }
+
+    /// Whether an occurrence between `before` and `after` on `line` passes the filter
+    pub fn keeps(&self, line: &str, before: &str, after: &str) -> bool {
+        match self {
+            Filter::WhereLine(regex) => regex.is_match(line),
+            Filter::SkipLine(regex) => !regex.is_match(line),
+            Filter::Before(regex) => regex.is_match(before),
+            Filter::SkipBefore(regex) => !regex.is_match(before),
+            Filter::After(regex) => regex.is_match(after),
+            Filter::SkipAfter(regex) => !regex.is_match(after),
+        }
+    }
+}
+
+/// Compiles `regex`, reporting errors against the `pattern` it was built from
+fn compile(regex: &str, pattern: &str) -> Result<Regex> {
+    Regex::new(regex).map_err(|e| format!("Invalid regex `{pattern}`: {e}").into())
+}
+
+#[cfg(test)]
+mod test_filter {
+    use super::Filter;
+
+    #[test]
+    fn neighbourhood() {
+        let line = "import { user } from legacy.user";
+        let (before, after) = ("import { ", " } from legacy.user");
+
+        assert!(Filter::where_line("import|from")
+            .unwrap()
+            .keeps(line, before, after));
+        assert!(!Filter::skip_line("legacy")
+            .unwrap()
+            .keeps(line, before, after));
+        assert!(Filter::before(r"\{ ").unwrap().keeps(line, before, after));
+        assert!(!Filter::before("import").unwrap().keeps(line, before, after));
+        assert!(Filter::after(r" \}").unwrap().keeps(line, before, after));
+        assert!(!Filter::skip_after(" ").unwrap().keeps(line, before, after));
+        assert!(Filter::skip_before(r"legacy\.")
+            .unwrap()
+            .keeps(line, before, after));
+    }
+}
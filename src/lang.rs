@@ -0,0 +1,305 @@
+use derive_more::Display;
+
+use crate::location::Location;
+
+/// Languages whose keywords and identifier syntax renames are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    Rust,
+    /// TypeScript and JavaScript
+    Ts,
+    Python,
+    Go,
+    Java,
+}
+
+/// What to do with renames that produce a keyword or an invalid identifier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnInvalid {
+    /// Report them and rename anyway
+    #[default]
+    Warn,
+    /// Report them and leave the input untouched
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Problem {
+    #[display(fmt = "is a keyword")]
+    Keyword,
+
+    #[display(fmt = "is not a valid identifier")]
+    NotAnIdentifier,
+}
+
+/// A rename that turns a valid identifier into a keyword or something that isn't an identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRename {
+    pub pos: usize,
+    pub len: usize,
+    pub location: Location,
+    /// The whole identifier once renamed
+    pub identifier: String,
+    pub problem: Problem,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Rust keywords that can't be used as raw identifiers either
+const RUST_NON_RAW: &[&str] = &["crate", "self", "Self", "super"];
+
+const TS_KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+const GO_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+impl Lang {
+    /// The language of a file with the given extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "rs" => Some(Lang::Rust),
+            "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => Some(Lang::Ts),
+            "py" | "pyi" => Some(Lang::Python),
+            "go" => Some(Lang::Go),
+            "java" => Some(Lang::Java),
+            _ => None,
+        }
+    }
+
+    pub fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Lang::Rust => RUST_KEYWORDS,
+            Lang::Ts => TS_KEYWORDS,
+            Lang::Python => PYTHON_KEYWORDS,
+            Lang::Go => GO_KEYWORDS,
+            Lang::Java => JAVA_KEYWORDS,
+        }
+    }
+
+    pub fn is_keyword(&self, s: &str) -> bool {
+        self.keywords().contains(&s)
+    }
+
+    pub fn is_identifier_char(&self, c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || (c == '$' && matches!(self, Lang::Ts | Lang::Java))
+    }
+
+    pub fn is_identifier(&self, s: &str) -> bool {
+        s.chars()
+            .next()
+            .is_some_and(|c| self.is_identifier_char(c) && !c.is_numeric())
+            && s.chars().all(|c| self.is_identifier_char(c))
+    }
+
+    /// What's wrong with `s` as an identifier, Rust raw identifiers (`r#type`) included
+    pub fn check(&self, s: &str) -> Option<Problem> {
+        if let Some(raw) = s.strip_prefix("r#").filter(|_| *self == Lang::Rust) {
+            return (!self.is_identifier(raw) || RUST_NON_RAW.contains(&raw))
+                .then_some(Problem::NotAnIdentifier);
+        }
+
+        if !self.is_identifier(s) {
+            Some(Problem::NotAnIdentifier)
+        } else if self.is_keyword(s) {
+            Some(Problem::Keyword)
+        } else {
+            None
+        }
+    }
+
+    /// `keyword` as a Rust raw identifier, when it can be one
+    pub fn escape(&self, keyword: &str) -> Option<String> {
+        (*self == Lang::Rust && self.is_keyword(keyword) && !RUST_NON_RAW.contains(&keyword))
+            .then(|| format!("r#{keyword}"))
+    }
+
+    /// Bounds of the identifier containing `buf[pos..pos + len]`, a leading `r#` included in Rust
+    pub fn identifier_around(&self, buf: &str, pos: usize, len: usize) -> (usize, usize) {
+        let mut start = buf[..pos]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| self.is_identifier_char(*c))
+            .last()
+            .map_or(pos, |(i, _)| i);
+        let end = buf[pos + len..]
+            .char_indices()
+            .find(|(_, c)| !self.is_identifier_char(*c))
+            .map_or(buf.len(), |(i, _)| pos + len + i);
+
+        if *self == Lang::Rust && buf[..start].ends_with("r#") {
+            start -= 2;
+        }
+
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod test_lang {
+    use super::{Lang, Problem};
+
+    #[test]
+    fn checks() {
+        assert_eq!(Lang::Rust.check("kind"), None);
+        assert_eq!(Lang::Rust.check("type"), Some(Problem::Keyword));
+        assert_eq!(Lang::Rust.check("r#type"), None);
+        assert_eq!(Lang::Rust.check("r#self"), Some(Problem::NotAnIdentifier));
+        assert_eq!(Lang::Ts.check("old-user"), Some(Problem::NotAnIdentifier));
+        assert_eq!(Lang::Ts.check("$scope"), None);
+        assert_eq!(Lang::Python.check("$scope"), Some(Problem::NotAnIdentifier));
+        assert_eq!(Lang::Go.check("2fast"), Some(Problem::NotAnIdentifier));
+        assert_eq!(Lang::Java.check("class"), Some(Problem::Keyword));
+    }
+
+    #[test]
+    fn identifier_bounds() {
+        let buf = "let my_kind = r#kind;";
+
+        assert_eq!(Lang::Rust.identifier_around(buf, 7, 4), (4, 11));
+        assert_eq!(Lang::Rust.identifier_around(buf, 16, 4), (14, 20));
+        assert_eq!(Lang::Rust.escape("type"), Some(String::from("r#type")));
+        assert_eq!(Lang::Rust.escape("self"), None);
+        assert_eq!(Lang::Go.escape("type"), None);
+    }
+}
@@ -0,0 +1,263 @@
+use std::ops::Range;
+
+use crate::{lang::Lang, range};
+
+/// Comment and string syntax shared by a family of languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Syntax {
+    /// `//` and `/* */` comments, `"`, `'` and `` ` `` strings (C, Rust, JS, Go, Java...)
+    CLike,
+    /// `#` comments, `"`, `'` and triple-quoted strings (Python, shell, Ruby, YAML...)
+    Hash,
+    /// `--` and `/* */` comments, `'` strings
+    Sql,
+    /// `<!-- -->` comments, quoted attribute values as strings
+    Html,
+}
+
+/// The kind of text a position falls in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Region {
+    #[value(name = "code")]
+    Code,
+    #[value(name = "comments")]
+    Comment,
+    #[value(name = "strings")]
+    String,
+}
+
+impl Syntax {
+    /// The syntax family of a file with the given extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "kt" | "scala" | "swift" | "rs"
+            | "go" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "css"
+            | "scss" | "less" | "php" | "dart" => Some(Syntax::CLike),
+            "py" | "pyi" | "sh" | "bash" | "zsh" | "rb" | "pl" | "r" | "yaml" | "yml" | "toml"
+            | "conf" | "cfg" | "mk" => Some(Syntax::Hash),
+            "sql" => Some(Syntax::Sql),
+            "html" | "htm" | "xml" | "svg" | "vue" => Some(Syntax::Html),
+            _ => None,
+        }
+    }
+}
+
+impl From<Lang> for Syntax {
+    fn from(value: Lang) -> Self {
+        match value {
+            Lang::Python => Syntax::Hash,
+            Lang::Rust | Lang::Ts | Lang::Go | Lang::Java => Syntax::CLike,
+        }
+    }
+}
+
+impl Region {
+    /// The region `pos` falls in among the comment and string `regions` of a buffer
+    pub fn at(regions: &[(Range<usize>, Region)], pos: usize) -> Region {
+        range::find(regions, pos, |(range, _)| range).map_or(Region::Code, |(_, region)| *region)
+    }
+}
+
+/// The comments and strings of `buf` in order, everything else being code
+/// `lifetimes` tells a Rust lifetime or label (`'a`) apart from a character literal
+pub fn regions(buf: &str, syntax: Syntax, lifetimes: bool) -> Vec<(Range<usize>, Region)> {
+    let mut regions = vec![];
+    let mut in_tag = false;
+    let mut pos = 0;
+
+    while let Some(c) = buf[pos..].chars().next() {
+        let rest = &buf[pos..];
+
+        let found = match syntax {
+            Syntax::CLike => {
+                if rest.starts_with("//") {
+                    Some((line_end(buf, pos), Region::Comment))
+                } else if rest.starts_with("/*") {
+                    Some((closing(buf, pos + 2, "*/"), Region::Comment))
+                } else if c == '\'' && lifetimes && is_lifetime(rest) {
+                    None
+                } else if c == '"' || c == '\'' || c == '`' {
+                    Some((quoted(buf, pos, c, c == '`'), Region::String))
+                } else {
+                    None
+                }
+            }
+            Syntax::Hash => {
+                if c == '#' {
+                    Some((line_end(buf, pos), Region::Comment))
+                } else if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+                    Some((closing(buf, pos + 3, &rest[..3]), Region::String))
+                } else if c == '"' || c == '\'' {
+                    Some((quoted(buf, pos, c, false), Region::String))
+                } else {
+                    None
+                }
+            }
+            Syntax::Sql => {
+                if rest.starts_with("--") {
+                    Some((line_end(buf, pos), Region::Comment))
+                } else if rest.starts_with("/*") {
+                    Some((closing(buf, pos + 2, "*/"), Region::Comment))
+                } else if c == '\'' {
+                    let mut end = quoted(buf, pos, c, true);
+
+                    // a doubled quote is an escaped quote: 'it''s'
+                    while buf[end..].starts_with(c) {
+                        end = quoted(buf, end, c, true);
+                    }
+
+                    Some((end, Region::String))
+                } else {
+                    None
+                }
+            }
+            Syntax::Html => {
+                if rest.starts_with("<!--") {
+                    Some((closing(buf, pos + 4, "-->"), Region::Comment))
+                } else if in_tag && (c == '"' || c == '\'') {
+                    Some((quoted(buf, pos, c, true), Region::String))
+                } else {
+                    match c {
+                        '<' => in_tag = true,
+                        '>' => in_tag = false,
+                        _ => {}
+                    }
+                    None
+                }
+            }
+        };
+
+        match found {
+            Some((end, region)) => {
+                regions.push((pos..end, region));
+                pos = end;
+            }
+            None => pos += c.len_utf8(),
+        }
+    }
+
+    regions
+}
+
+/// End of the line `pos` is on, excluding the line break
+fn line_end(buf: &str, pos: usize) -> usize {
+    buf[pos..].find('\n').map_or(buf.len(), |i| pos + i)
+}
+
+/// End of `delimiter` searched from `from`, or the end of `buf` when it's never closed
+fn closing(buf: &str, from: usize, delimiter: &str) -> usize {
+    buf[from..]
+        .find(delimiter)
+        .map_or(buf.len(), |i| from + i + delimiter.len())
+}
+
+/// End of the string opened by `quote` at `pos`. Backslashes escape the next character and
+/// strings end at the line break unless they are `multiline`
+pub(crate) fn quoted(buf: &str, pos: usize, quote: char, multiline: bool) -> usize {
+    let mut chars = buf[pos + quote.len_utf8()..].char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        let end = pos + quote.len_utf8() + i + c.len_utf8();
+
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' if !multiline => return end - 1,
+            c if c == quote => return end,
+            _ => {}
+        }
+    }
+
+    buf.len()
+}
+
+/// Whether `rest`, starting with a quote, is a lifetime or label such as `'a` rather than a
+/// character literal such as `'a'`
+fn is_lifetime(rest: &str) -> bool {
+    let mut chars = rest.chars().skip(1);
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.find(|c| !(c.is_alphanumeric() || *c == '_')) != Some('\'')
+}
+
+#[cfg(test)]
+mod test_lexer {
+    use super::{regions, Region, Syntax};
+
+    fn region_texts(buf: &str, syntax: Syntax, lifetimes: bool) -> Vec<(&str, Region)> {
+        regions(buf, syntax, lifetimes)
+            .into_iter()
+            .map(|(range, region)| (&buf[range], region))
+            .collect()
+    }
+
+    #[test]
+    fn c_like() {
+        let buf = "let s = \"a \\\" b\"; // note\n/* block\n */ f('x', `t`);";
+
+        assert_eq!(
+            region_texts(buf, Syntax::CLike, false),
+            vec![
+                ("\"a \\\" b\"", Region::String),
+                ("// note", Region::Comment),
+                ("/* block\n */", Region::Comment),
+                ("'x'", Region::String),
+                ("`t`", Region::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_lifetimes() {
+        let buf = "fn f<'a>(s: &'a str) -> char { 'a' }";
+
+        assert_eq!(
+            region_texts(buf, Syntax::CLike, true),
+            vec![("'a'", Region::String)]
+        );
+    }
+
+    #[test]
+    fn hash() {
+        let buf = "x = 'user' # user\ny = \"\"\"doc\nuser\"\"\"";
+
+        assert_eq!(
+            region_texts(buf, Syntax::Hash, false),
+            vec![
+                ("'user'", Region::String),
+                ("# user", Region::Comment),
+                ("\"\"\"doc\nuser\"\"\"", Region::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn sql_and_html() {
+        assert_eq!(
+            region_texts("SELECT 'it''s' -- user", Syntax::Sql, false),
+            vec![("'it''s'", Region::String), ("-- user", Region::Comment)]
+        );
+
+        assert_eq!(
+            region_texts(
+                "<a title=\"user\">user's</a><!-- user -->",
+                Syntax::Html,
+                false
+            ),
+            vec![
+                ("\"user\"", Region::String),
+                ("<!-- user -->", Region::Comment)
+            ]
+        );
+    }
+
+    #[test]
+    fn region_lookup() {
+        let buf = "user // user";
+        let regions = regions(buf, Syntax::CLike, false);
+
+        assert_eq!(Region::at(&regions, 0), Region::Code);
+        assert_eq!(Region::at(&regions, 8), Region::Comment);
+    }
+}
@@ -1,7 +1,23 @@
+pub mod affix;
 pub mod casing;
+pub mod collision;
+pub mod conventions;
+pub mod dictionary;
+pub mod directive;
 pub mod error;
+pub mod filter;
+pub mod lang;
+pub mod lexer;
+pub mod location;
+pub mod markdown;
+pub mod pattern;
+pub mod plural;
+pub mod range;
 pub mod record;
+pub mod skipped;
+pub mod structured;
 pub mod task;
+pub mod template;
 pub mod token;
 
 pub use error::{Error, Result};
@@ -0,0 +1,41 @@
+use derive_more::Display;
+
+/// A 1-based line and column (in characters) within a buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[display(fmt = "{}:{}", line, column)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Byte offsets at which the lines of `buf` start
+    pub fn line_starts(buf: &str) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(buf.match_indices('\n').map(|(i, _)| i + 1))
+            .collect()
+    }
+
+    pub fn of(buf: &str, pos: usize) -> Self {
+        let line_start = buf[..pos].rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: buf[..pos].matches('\n').count() + 1,
+            column: buf[line_start..pos].chars().count() + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_location {
+    use super::Location;
+
+    #[test]
+    fn line_and_column() {
+        let buf = "first\nsécond line";
+
+        assert_eq!(Location::of(buf, 0), Location { line: 1, column: 1 });
+        assert_eq!(Location::of(buf, 6), Location { line: 2, column: 1 });
+        assert_eq!(Location::of(buf, 14), Location { line: 2, column: 8 });
+    }
+}
@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+/// The part of a Markdown document renames are limited to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MarkdownScope {
+    /// Fenced code blocks and inline code spans
+    Code,
+    /// Everything but code
+    Prose,
+}
+
+/// Fenced code blocks and inline code spans of a Markdown document, in order
+pub fn code_ranges(buf: &str) -> Vec<Range<usize>> {
+    let fences = fences(buf);
+    let mut ranges = vec![];
+    let mut pos = 0;
+
+    for fence in fences.iter() {
+        ranges.extend(code_spans(buf, pos..fence.start));
+        ranges.push(fence.clone());
+        pos = fence.end;
+    }
+    ranges.extend(code_spans(buf, pos..buf.len()));
+
+    ranges
+}
+
+/// Fenced code blocks from their opening ``` or ~~~ line to the end of their closing one,
+/// or to the end of the document when they're never closed
+fn fences(buf: &str) -> Vec<Range<usize>> {
+    let mut fences = vec![];
+    let mut open: Option<(usize, char, usize)> = None;
+    let mut line_start = 0;
+
+    for line in buf.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let run = marker.map_or(0, |c| trimmed.chars().take_while(|t| *t == c).count());
+
+        match (open, marker) {
+            (None, Some(c)) if indent <= 3 && run >= 3 => open = Some((line_start, c, run)),
+            (Some((start, c, len)), Some(m))
+                if m == c && run >= len && trimmed[run..].trim().is_empty() =>
+            {
+                fences.push(start..line_end);
+                open = None;
+            }
+            _ => {}
+        }
+
+        line_start = line_end;
+    }
+
+    if let Some((start, _, _)) = open {
+        fences.push(start..buf.len());
+    }
+
+    fences
+}
+
+/// Inline code spans within `range`: a run of backticks up to the next run of the same length
+fn code_spans(buf: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let text = &buf[range.clone()];
+    let mut spans = vec![];
+    let mut pos = 0;
+
+    while let Some(start) = text[pos..].find('`').map(|i| pos + i) {
+        let run = text[start..].chars().take_while(|c| *c == '`').count();
+        let mut search = start + run;
+        pos = search;
+
+        while let Some(close) = text[search..].find('`').map(|i| search + i) {
+            let close_run = text[close..].chars().take_while(|c| *c == '`').count();
+
+            if close_run == run {
+                spans.push(range.start + start..range.start + close + run);
+                pos = close + run;
+                break;
+            }
+
+            search = close + close_run;
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod test_markdown {
+    use super::code_ranges;
+    use crate::range::contains;
+
+    #[test]
+    fn fences_and_spans() {
+        let buf = "The `user` id\n\n```rust\nlet user = 1;\n```\nA ``user ` name`` and `user\n~~~\nuser\n";
+        let texts: Vec<&str> = code_ranges(buf).into_iter().map(|r| &buf[r]).collect();
+
+        assert_eq!(
+            texts,
+            vec![
+                "`user`",
+                "```rust\nlet user = 1;\n```\n",
+                "``user ` name``",
+                "~~~\nuser\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let buf = "user `user` user";
+        let ranges = code_ranges(buf);
+
+        assert!(!contains(&ranges, 0));
+        assert!(contains(&ranges, 6));
+        assert!(!contains(&ranges, 12));
+    }
+}
@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::{casing::Casing, token::Token, Result};
+
+/// A regex candidate such as `user(\w+)Dto`, matched against whole identifiers in any casing
+#[derive(Debug)]
+pub struct Pattern {
+    regex: Regex,
+    /// Casing the regex is written in, identifiers are rendered in it before matching
+    casing: Casing,
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|e| format!("Invalid regex candidate: {e}"))?;
+
+        let casing = Casing::detect_casing(&literal_text(pattern)).map_err(|_| {
+            format!("Failed to detect the casing of the text outside the groups of `{pattern}`")
+        })?;
+
+        Ok(Self { regex, casing })
+    }
+
+    /// Number of capture groups in the pattern
+    pub fn groups(&self) -> usize {
+        self.regex.captures_len() - 1
+    }
+
+    /// The words of `identifier` followed by the words of each group when it matches the
+    /// pattern as a whole, groups that didn't take part in the match being empty
+    /// Example: "USER_PROFILE_DTO" matches "user(\w+)Dto" as "userProfileDto" with "Profile" captured
+    pub fn captures(&self, identifier: &str) -> Option<Vec<Token>> {
+        let rendered = Token::from_str(identifier)
+            .ok()?
+            .try_to_casing(&self.casing)
+            .ok()?;
+        let captures = self.regex.captures(&rendered)?;
+
+        captures
+            .iter()
+            .map(|group| match group {
+                Some(group) => Token::from_str(group.as_str()).ok(),
+                None => Some(Token::join(&[])),
+            })
+            .collect()
+    }
+}
+
+/// Identifier-like runs of `buf` with their positions, hyphenated runs included
+pub fn identifiers(buf: &str) -> impl Iterator<Item = (usize, &str)> {
+    buf.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '$' || c == '@'))
+        .scan(0, move |pos, identifier| {
+            let start = *pos;
+            *pos += identifier.len();
+            // the delimiter that ended the identifier
+            *pos += buf[*pos..].chars().next().map_or(0, |c| c.len_utf8());
+            Some((start, identifier))
+        })
+        .filter(|(_, identifier)| !identifier.is_empty())
+}
+
+/// The pattern with its groups and regex syntax removed, e.g. `userDto` for `user(\w+)Dto`
+fn literal_text(pattern: &str) -> String {
+    let mut text = String::new();
+    let mut depth = 0;
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if depth == 0 && (c.is_alphanumeric() || "_- ./".contains(c)) => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod test_pattern {
+    use super::{identifiers, Pattern};
+
+    #[test]
+    fn captures_in_every_casing() {
+        let pattern = Pattern::parse(r"user(\w+)Dto").unwrap();
+
+        for identifier in [
+            "userProfileDto",
+            "UserProfileDto",
+            "user_profile_dto",
+            "USER-PROFILE-DTO",
+        ] {
+            let captures = pattern.captures(identifier).unwrap();
+            assert_eq!(captures[1].to_snake_case(), "profile");
+        }
+
+        assert!(pattern.captures("userDto").is_none());
+        assert!(pattern.captures("accountProfileDto").is_none());
+    }
+
+    #[test]
+    fn identifier_runs() {
+        let found: Vec<_> = identifiers("let user_dto = (a-b, $c);").collect();
+
+        assert_eq!(
+            found,
+            vec![(0, "let"), (4, "user_dto"), (16, "a-b"), (21, "$c")]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+/// Irregular English plurals, extended through [`Options::irregulars`](crate::task::Options)
+pub const IRREGULARS: &[(&str, &str)] = &[
+    ("person", "people"),
+    ("child", "children"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("foot", "feet"),
+    ("tooth", "teeth"),
+    ("ox", "oxen"),
+    ("datum", "data"),
+    ("index", "indices"),
+    ("matrix", "matrices"),
+    ("vertex", "vertices"),
+    ("criterion", "criteria"),
+    ("analysis", "analyses"),
+    ("axis", "axes"),
+    ("leaf", "leaves"),
+    ("life", "lives"),
+    ("knife", "knives"),
+    ("wife", "wives"),
+    ("half", "halves"),
+    ("sheep", "sheep"),
+    ("fish", "fish"),
+    ("series", "series"),
+    ("species", "species"),
+];
+
+/// The English plural of a lowercase `word`, looking it up in `irregulars`
+/// before [`IRREGULARS`] and falling back to the regular suffix rules
+pub fn pluralize(word: &str, irregulars: &[(String, String)]) -> String {
+    let lower = word.to_lowercase();
+
+    let irregular = irregulars
+        .iter()
+        .map(|(singular, plural)| (singular.as_str(), plural.as_str()))
+        .chain(IRREGULARS.iter().copied())
+        .find(|(singular, _)| singular.to_lowercase() == lower);
+
+    if let Some((_, plural)) = irregular {
+        return plural.to_string();
+    }
+
+    let ends_with_consonant_y = lower.ends_with('y')
+        && lower
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| !"aeiou".contains(c));
+
+    if ends_with_consonant_y {
+        format!("{}ies", &word[..word.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        format!("{word}es")
+    } else {
+        format!("{word}s")
+    }
+}
+
+/// Length of the possessive marker after the plural at `start..end` in `buf`: `'s` after a
+/// plural that doesn't end in "s", a lone `'` after one that does
+pub fn possessive_len(buf: &str, start: usize, end: usize) -> Option<usize> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let rest = &buf[end..];
+    let apostrophe = rest.chars().next().filter(|c| APOSTROPHES.contains(c))?;
+    let after = &rest[apostrophe.len_utf8()..];
+
+    if buf[start..end].ends_with(['s', 'S']) {
+        // NOTE: an apostrophe before the word makes it a quote rather than a possessive
+        // Example: 'users' is quoted, users' isn't
+        let is_quoted = buf[..start]
+            .trim_end_matches(is_word_char)
+            .ends_with(apostrophe);
+
+        (!is_quoted && !after.starts_with(is_word_char)).then_some(apostrophe.len_utf8())
+    } else {
+        let mut chars = after.chars();
+
+        (matches!(chars.next(), Some('s' | 'S')) && !chars.next().is_some_and(is_word_char))
+            .then_some(apostrophe.len_utf8() + 1)
+    }
+}
+
+/// The possessive marker following `plural`, using the apostrophe of `marker`
+pub fn possessive(plural: &str, marker: &str) -> String {
+    let apostrophe = marker.chars().next().unwrap_or('\'');
+
+    if plural.ends_with(['s', 'S']) {
+        apostrophe.to_string()
+    } else if plural.chars().any(char::is_uppercase) && !plural.chars().any(char::is_lowercase) {
+        format!("{apostrophe}S")
+    } else {
+        format!("{apostrophe}s")
+    }
+}
+
+const APOSTROPHES: [char; 2] = ['\'', '\u{2019}'];
+
+#[cfg(test)]
+mod test_plural {
+    use super::{pluralize, possessive, possessive_len};
+
+    #[test]
+    fn regular_plurals() {
+        let cases = [
+            ("user", "users"),
+            ("box", "boxes"),
+            ("status", "statuses"),
+            ("branch", "branches"),
+            ("category", "categories"),
+            ("day", "days"),
+            ("ID", "IDs"),
+        ];
+
+        for (singular, plural) in cases {
+            assert_eq!(pluralize(singular, &[]), plural);
+        }
+    }
+
+    #[test]
+    fn irregular_plurals() {
+        assert_eq!(pluralize("person", &[]), "people");
+        assert_eq!(pluralize("child", &[]), "children");
+        assert_eq!(pluralize("sheep", &[]), "sheep");
+
+        let custom = vec![(String::from("cactus"), String::from("cacti"))];
+        assert_eq!(pluralize("cactus", &custom), "cacti");
+    }
+
+    #[test]
+    fn possessives() {
+        assert_eq!(possessive_len("people's", 0, 6), Some(2));
+        assert_eq!(possessive_len("users' names", 0, 5), Some(1));
+        assert_eq!(possessive_len("users\u{2019} names", 0, 5), Some(3));
+        assert_eq!(possessive_len("'users'", 1, 6), None);
+        assert_eq!(possessive_len("people'sx", 0, 6), None);
+
+        assert_eq!(possessive("customers", "'s"), "'");
+        assert_eq!(possessive("people", "'"), "'s");
+        assert_eq!(possessive("PEOPLE", "\u{2019}"), "\u{2019}S");
+    }
+}
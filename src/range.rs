@@ -0,0 +1,34 @@
+use std::ops::Range;
+
+/// The entry of `entries` whose range contains `pos`, entries being sorted by
+/// non-overlapping ranges
+pub fn find<T>(entries: &[T], pos: usize, range: impl Fn(&T) -> &Range<usize>) -> Option<&T> {
+    let index = entries.partition_point(|entry| range(entry).end <= pos);
+    entries
+        .get(index)
+        .filter(|entry| range(entry).contains(&pos))
+}
+
+/// Whether `pos` falls in one of the sorted, non-overlapping `ranges`
+pub fn contains(ranges: &[Range<usize>], pos: usize) -> bool {
+    find(ranges, pos, |range| range).is_some()
+}
+
+#[cfg(test)]
+mod test_range {
+    use super::{contains, find};
+
+    #[test]
+    fn lookup() {
+        let ranges = [2..4, 6..9];
+
+        assert!(!contains(&ranges, 1));
+        assert!(contains(&ranges, 2));
+        assert!(!contains(&ranges, 4));
+        assert!(contains(&ranges, 8));
+        assert!(!contains(&ranges, 9));
+
+        let entries = [(0..3, 'a'), (5..6, 'b')];
+        assert_eq!(find(&entries, 5, |(range, _)| range), Some(&(5..6, 'b')));
+    }
+}
@@ -1,15 +1,46 @@
 use std::collections::{btree_map::Iter, BTreeMap};
 
-use crate::casing::Casing;
+use crate::{casing::Casing, token::Token};
 
 #[derive(Debug, Clone)]
 pub struct Record {
     pub pos: usize,
     pub len: usize,
     pub casing: Casing,
+    /// The match has no recognised casing and is rendered by mapping its character cases
+    /// onto the rename
+    pub mixed: bool,
+    /// The record also covers the affixes of the occurrence, which are swapped for those of
+    /// the rename
+    pub swap_affixes: bool,
+    /// The match is a plural form of the candidate and takes the plural of the rename
+    pub plural: bool,
+    /// Length of the possessive marker following a plural match, rewritten to suit the
+    /// plural of the rename
+    pub possessive: Option<usize>,
+    /// Index of the candidate spelling the match was found for
+    pub candidate: usize,
+    /// Words of a regex candidate match followed by those of its groups, empty otherwise
+    pub groups: Vec<Token>,
 }
 
-#[derive(Debug)]
+impl Record {
+    pub fn new(pos: usize, len: usize, casing: Casing) -> Self {
+        Self {
+            pos,
+            len,
+            casing,
+            mixed: false,
+            swap_affixes: false,
+            plural: false,
+            possessive: None,
+            candidate: 0,
+            groups: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct Records {
     pub map: BTreeMap<usize, Record>,
 }
@@ -22,15 +53,27 @@ impl Records {
     }
 
     pub fn try_insert(&mut self, pos: usize, len: usize, casing: Casing) -> Result<(), String> {
-        if self.map.contains_key(&pos) {
+        self.try_insert_record(Record::new(pos, len, casing))
+    }
+
+    pub fn try_insert_record(&mut self, record: Record) -> Result<(), String> {
+        if self.map.contains_key(&record.pos) {
             return Err("Key already present".into());
         }
 
-        self.map.insert(pos, Record { pos, len, casing });
+        self.map.insert(record.pos, record);
 
         Ok(())
     }
 
+    /// Whether any record covers part of `pos..pos + len`
+    pub fn overlaps(&self, pos: usize, len: usize) -> bool {
+        self.map
+            .range(..pos + len)
+            .next_back()
+            .is_some_and(|(_, record)| record.pos + record.len > pos)
+    }
+
     pub fn iter(&self) -> Iter<'_, usize, Record> {
         self.map.iter()
     }
@@ -0,0 +1,81 @@
+use derive_more::Display;
+
+use crate::{casing::Casing, location::Location};
+
+/// An occurrence of the candidate that differs from it only by case and is left alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub pos: usize,
+    pub len: usize,
+    pub location: Location,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum SkipReason {
+    #[display(fmt = "mixed casing")]
+    MixedCasing,
+
+    #[display(fmt = "flat form of a multi-word term (see --flat)")]
+    Flat,
+
+    #[display(fmt = "{} casing is not searched", "_0.name()")]
+    NotSearched(Casing),
+}
+
+/// Finds every occurrence of `needle` in `haystack` ignoring case, as byte ranges
+pub fn find_case_insensitive(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    let mut matches = vec![];
+
+    if needle.is_empty() {
+        return matches;
+    }
+
+    for (start, _) in haystack.char_indices() {
+        let mut rest = haystack[start..].char_indices();
+        let mut lowered = vec![];
+        let mut end = start;
+
+        while lowered.len() < needle.len() {
+            let Some((i, c)) = rest.next() else {
+                break;
+            };
+
+            lowered.extend(c.to_lowercase());
+            end = start + i + c.len_utf8();
+        }
+
+        if lowered == needle {
+            matches.push((start, end - start));
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod test_skipped {
+    use super::{find_case_insensitive, SkipReason};
+    use crate::casing::Casing;
+
+    #[test]
+    fn case_insensitive_matches() {
+        assert_eq!(
+            find_case_insensitive("rEsult RESULT res", "result"),
+            vec![(0, 6), (7, 6)]
+        );
+        assert_eq!(
+            find_case_insensitive("ÜBER über", "über"),
+            vec![(0, 5), (6, 5)]
+        );
+    }
+
+    #[test]
+    fn reason_names_cli_casing() {
+        assert_eq!(
+            SkipReason::NotSearched(Casing::UpperSnake).to_string(),
+            "upper-snake casing is not searched"
+        );
+    }
+}
@@ -0,0 +1,547 @@
+use std::ops::Range;
+
+use crate::{lexer::quoted, range};
+
+/// Data formats whose keys and values can be told apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+/// Where an occurrence sits in a data file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Position {
+    #[value(name = "keys")]
+    Key,
+    #[value(name = "values")]
+    Value,
+}
+
+impl Format {
+    /// The format of a file with the given extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" | "jsonc" | "json5" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    /// The keys and string values of `buf` in order, quotes left out
+    pub fn positions(&self, buf: &str) -> Vec<(Range<usize>, Position)> {
+        let mut positions = match self {
+            Format::Json => json(buf),
+            Format::Yaml => yaml(buf),
+            Format::Toml => toml(buf),
+        };
+
+        positions.retain(|(range, _)| !range.is_empty());
+        positions
+    }
+}
+
+impl Position {
+    /// The position of the text at `range` among the `positions` of a buffer, if it lies
+    /// entirely within a key or a value
+    pub fn of(positions: &[(Range<usize>, Position)], range: Range<usize>) -> Option<Position> {
+        range::find(positions, range.start, |(r, _)| r)
+            .filter(|(r, _)| range.end <= r.end)
+            .map(|(_, position)| *position)
+    }
+}
+
+fn json(buf: &str) -> Vec<(Range<usize>, Position)> {
+    let mut positions = vec![];
+    let mut objects: Vec<bool> = vec![];
+    let mut expects_key = false;
+    let mut pos = 0;
+
+    while let Some(c) = buf[pos..].chars().next() {
+        match c {
+            '{' => {
+                objects.push(true);
+                expects_key = true;
+            }
+            '[' => {
+                objects.push(false);
+                expects_key = false;
+            }
+            '}' | ']' => {
+                objects.pop();
+                expects_key = false;
+            }
+            ':' => expects_key = false,
+            ',' => expects_key = objects.last() == Some(&true),
+            '"' => {
+                let end = quoted(buf, pos, c, false);
+                let position = if expects_key {
+                    Position::Key
+                } else {
+                    Position::Value
+                };
+
+                positions.push((inner(buf, pos, end, 1), position));
+                pos = end;
+                continue;
+            }
+            _ => {}
+        }
+
+        pos += c.len_utf8();
+    }
+
+    positions
+}
+
+fn yaml(buf: &str) -> Vec<(Range<usize>, Position)> {
+    let mut positions = vec![];
+    // indentation of the line that opened a `|` or `>` block scalar
+    let mut block: Option<usize> = None;
+    // end of a flow collection spanning several lines
+    let mut flow_end = 0;
+    let mut line_start = 0;
+
+    for line in buf.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        if start < flow_end {
+            continue;
+        }
+
+        let content = line.trim_end_matches(['\n', '\r']);
+        let indent = content.len() - content.trim_start_matches(' ').len();
+        let trimmed = &content[indent..];
+
+        if let Some(block_indent) = block {
+            if trimmed.is_empty() {
+                continue;
+            }
+            if indent > block_indent {
+                positions.push((start + indent..start + content.len(), Position::Value));
+                continue;
+            }
+            block = None;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "..." {
+            continue;
+        }
+
+        let mut item = indent;
+        while content[item..].starts_with("- ") {
+            item += 2;
+            item += content[item..].len() - content[item..].trim_start_matches(' ').len();
+        }
+
+        let value = match yaml_key(content, item) {
+            Some((key, value)) => {
+                positions.push((start + key.start..start + key.end, Position::Key));
+                value
+            }
+            None => item,
+        };
+
+        let text = strip_comment(&content[value..]).trim_end();
+
+        if text.starts_with(['|', '>']) {
+            block = Some(indent);
+        } else if text.starts_with(['{', '[']) {
+            flow_end = yaml_flow(buf, start + value, &mut positions);
+        } else if text.starts_with(['"', '\'']) {
+            let end = quoted(content, value, text.chars().next().unwrap_or('"'), false);
+            positions.push((shift(inner(content, value, end, 1), start), Position::Value));
+        } else if !text.is_empty() {
+            positions.push((start + value..start + value + text.len(), Position::Value));
+        }
+    }
+
+    positions
+}
+
+/// Records the keys and values of the YAML flow collection opened at `pos`, such as
+/// `{userId: 1, tags: [a, "b"]}`, and returns where it ends
+fn yaml_flow(buf: &str, mut pos: usize, positions: &mut Vec<(Range<usize>, Position)>) -> usize {
+    // whether each open collection is a mapping
+    let mut mappings: Vec<bool> = vec![];
+    let mut expects_key = false;
+
+    while let Some(c) = buf[pos..].chars().next() {
+        let position = if expects_key {
+            Position::Key
+        } else {
+            Position::Value
+        };
+
+        match c {
+            '{' | '[' => {
+                mappings.push(c == '{');
+                expects_key = c == '{';
+            }
+            '}' | ']' => {
+                mappings.pop();
+                expects_key = false;
+
+                if mappings.is_empty() {
+                    return pos + 1;
+                }
+            }
+            ',' => expects_key = mappings.last() == Some(&true),
+            ':' => expects_key = false,
+            '#' if buf[..pos].ends_with(char::is_whitespace) => {
+                pos = buf[pos..].find('\n').map_or(buf.len(), |i| pos + i);
+                continue;
+            }
+            '"' | '\'' => {
+                let end = quoted(buf, pos, c, true);
+                positions.push((inner(buf, pos, end, 1), position));
+                pos = end;
+                continue;
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let len = flow_scalar_len(&buf[pos..]);
+                positions.push((pos..pos + len, position));
+                pos += len;
+                continue;
+            }
+        }
+
+        pos += c.len_utf8();
+    }
+
+    buf.len()
+}
+
+/// Length of the plain scalar `s` starts with inside a flow collection, trailing spaces left out
+fn flow_scalar_len(s: &str) -> usize {
+    for (i, c) in s.char_indices() {
+        let ends_key = c == ':'
+            && match s[i + 1..].chars().next() {
+                Some(next) => next.is_whitespace() || ",]}".contains(next),
+                None => true,
+            };
+
+        if ends_key || ",[]{}\n".contains(c) {
+            return s[..i].trim_end().len();
+        }
+    }
+
+    s.trim_end().len()
+}
+
+/// The key of a YAML mapping entry starting at `from` in `line`, and where its value starts
+fn yaml_key(line: &str, from: usize) -> Option<(Range<usize>, usize)> {
+    let item = &line[from..];
+
+    let (key, after) = if item.starts_with(['"', '\'']) {
+        let end = quoted(line, from, item.chars().next()?, false);
+        (inner(line, from, end, 1), end)
+    } else {
+        let colon = item
+            .match_indices(':')
+            .map(|(i, _)| from + i)
+            .find(|i| line[i + 1..].is_empty() || line[i + 1..].starts_with(' '))?;
+        (from..from + line[from..colon].trim_end().len(), colon)
+    };
+
+    let rest = line[after..].strip_prefix(':')?;
+    let value = line.len() - rest.trim_start().len();
+
+    Some((key, value))
+}
+
+fn toml(buf: &str) -> Vec<(Range<usize>, Position)> {
+    let mut positions = vec![];
+    let mut multiline: Option<&str> = None;
+    let mut nesting = Nesting::default();
+    let mut line_start = 0;
+
+    for line in buf.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(delimiter) = multiline {
+            let end = content.find(delimiter);
+
+            positions.push((start..start + end.unwrap_or(content.len()), Position::Value));
+            if end.is_some() {
+                multiline = None;
+            }
+            continue;
+        }
+
+        if !nesting.open.is_empty() {
+            multiline = toml_value(content, 0, start, &mut nesting, &mut positions);
+            continue;
+        }
+
+        let indent = content.len() - content.trim_start().len();
+        let trimmed = &content[indent..];
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') {
+            let open = trimmed.chars().take_while(|c| *c == '[').count();
+            let close = trimmed.find(']').unwrap_or(trimmed.len());
+            positions.push((start + indent + open..start + indent + close, Position::Key));
+            continue;
+        }
+
+        let Some(equals) = toml_equals(content) else {
+            continue;
+        };
+
+        let key = content[indent..equals].trim_end();
+        let key = if key.starts_with(['"', '\'']) && key.len() >= 2 {
+            start + indent + 1..start + indent + key.len() - 1
+        } else {
+            start + indent..start + indent + key.len()
+        };
+        positions.push((key, Position::Key));
+
+        multiline = toml_value(content, equals + 1, start, &mut nesting, &mut positions);
+    }
+
+    positions
+}
+
+/// Brackets left open by a TOML value spanning several lines, as in a multi-line array
+#[derive(Debug, Default)]
+struct Nesting {
+    /// Whether each open bracket is an inline table rather than an array
+    open: Vec<bool>,
+    expects_key: bool,
+}
+
+/// Records the strings of the TOML value starting at `from` in `line`, and the keys of its
+/// inline tables, keeping track of the brackets it leaves open in `nesting`.
+/// Returns the delimiter of a multi-line string left open
+fn toml_value<'a>(
+    line: &'a str,
+    from: usize,
+    line_start: usize,
+    nesting: &mut Nesting,
+    positions: &mut Vec<(Range<usize>, Position)>,
+) -> Option<&'a str> {
+    let is_key_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+    let mut pos = from;
+
+    while let Some(c) = line[pos..].chars().next() {
+        let rest = &line[pos..];
+
+        if c == '#' {
+            break;
+        }
+
+        if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+            let delimiter = &rest[..3];
+
+            match line[pos + 3..].find(delimiter) {
+                Some(i) => {
+                    let start = line_start + pos + 3;
+                    positions.push((start..start + i, Position::Value));
+                    pos += 3 + i + 3;
+                }
+                None => {
+                    let start = line_start + pos + 3;
+                    positions.push((start..line_start + line.len(), Position::Value));
+                    return Some(delimiter);
+                }
+            }
+        } else if c == '"' || c == '\'' {
+            let end = quoted(line, pos, c, false);
+            let position = if nesting.expects_key {
+                Position::Key
+            } else {
+                Position::Value
+            };
+
+            positions.push((shift(inner(line, pos, end, 1), line_start), position));
+            pos = end;
+        } else if nesting.expects_key && is_key_char(c) {
+            let len = rest.find(|c| !is_key_char(c)).unwrap_or(rest.len());
+
+            positions.push((line_start + pos..line_start + pos + len, Position::Key));
+            pos += len;
+        } else {
+            match c {
+                '[' | '{' => {
+                    nesting.open.push(c == '{');
+                    nesting.expects_key = c == '{';
+                }
+                ']' | '}' => {
+                    nesting.open.pop();
+                    nesting.expects_key = false;
+                }
+                ',' => nesting.expects_key = nesting.open.last() == Some(&true),
+                '=' => nesting.expects_key = false,
+                _ => {}
+            }
+            pos += c.len_utf8();
+        }
+    }
+
+    None
+}
+
+/// Position of the `=` of a TOML key/value pair, skipping quoted keys
+fn toml_equals(line: &str) -> Option<usize> {
+    let mut pos = 0;
+
+    while let Some(c) = line[pos..].chars().next() {
+        match c {
+            '=' => return Some(pos),
+            '"' | '\'' => pos = quoted(line, pos, c, false),
+            _ => pos += c.len_utf8(),
+        }
+    }
+
+    None
+}
+
+/// `start..end` without the `quote_len` quote characters at either end
+fn inner(s: &str, start: usize, end: usize, quote_len: usize) -> Range<usize> {
+    let closed = end - start > quote_len && s[..end].ends_with(['"', '\'']);
+    start + quote_len..if closed { end - quote_len } else { end }
+}
+
+fn shift(range: Range<usize>, by: usize) -> Range<usize> {
+    range.start + by..range.end + by
+}
+
+/// `s` up to a `#` comment preceded by whitespace
+fn strip_comment(s: &str) -> &str {
+    s.match_indices(" #").next().map_or(s, |(i, _)| &s[..i])
+}
+
+#[cfg(test)]
+mod test_structured {
+    use super::{Format, Position};
+
+    fn texts(buf: &str, format: Format) -> Vec<(&str, Position)> {
+        format
+            .positions(buf)
+            .into_iter()
+            .map(|(range, position)| (&buf[range], position))
+            .collect()
+    }
+
+    #[test]
+    fn json() {
+        let buf = r#"{"userId": "userId@example.com", "list": [{"userId": 1}, "a\"b"]}"#;
+
+        assert_eq!(
+            texts(buf, Format::Json),
+            vec![
+                ("userId", Position::Key),
+                ("userId@example.com", Position::Value),
+                ("list", Position::Key),
+                ("userId", Position::Key),
+                ("a\\\"b", Position::Value),
+            ]
+        );
+    }
+
+    #[test]
+    fn yaml() {
+        let buf = "# userId\nuserId: userId # note\n\"quoted key\": 'v'\nitems:\n  - name: a\n  - plain\ntext: |\n  line one\nnext: 1\n";
+
+        assert_eq!(
+            texts(buf, Format::Yaml),
+            vec![
+                ("userId", Position::Key),
+                ("userId", Position::Value),
+                ("quoted key", Position::Key),
+                ("v", Position::Value),
+                ("items", Position::Key),
+                ("name", Position::Key),
+                ("a", Position::Value),
+                ("plain", Position::Value),
+                ("text", Position::Key),
+                ("line one", Position::Value),
+                ("next", Position::Key),
+                ("1", Position::Value),
+            ]
+        );
+    }
+
+    #[test]
+    fn yaml_flow() {
+        let buf = "map: {userId: x, \"a b\": [userId, 'c']}\nlist: [\n  userId,\n  { k: v },\n]\nnext: userId\n";
+
+        assert_eq!(
+            texts(buf, Format::Yaml),
+            vec![
+                ("map", Position::Key),
+                ("userId", Position::Key),
+                ("x", Position::Value),
+                ("a b", Position::Key),
+                ("userId", Position::Value),
+                ("c", Position::Value),
+                ("list", Position::Key),
+                ("userId", Position::Value),
+                ("k", Position::Key),
+                ("v", Position::Value),
+                ("next", Position::Key),
+                ("userId", Position::Value),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml() {
+        let buf = "[user.profile]\nuserId = \"userId\" # note\n\"a=b\" = ['x', \"y\"]\ndoc = \"\"\"\nuserId\n\"\"\"\nowner = { userId = \"userId\", \"q\" = 1 }\n";
+
+        assert_eq!(
+            texts(buf, Format::Toml),
+            vec![
+                ("user.profile", Position::Key),
+                ("userId", Position::Key),
+                ("userId", Position::Value),
+                ("a=b", Position::Key),
+                ("x", Position::Value),
+                ("y", Position::Value),
+                ("doc", Position::Key),
+                ("userId", Position::Value),
+                ("owner", Position::Key),
+                ("userId", Position::Key),
+                ("userId", Position::Value),
+                ("q", Position::Key),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_multiline_array() {
+        let buf = "users = [\n  \"userId\", # first\n  [\"nested\"],\n]\n[userId]\nkey = 1\n";
+
+        assert_eq!(
+            texts(buf, Format::Toml),
+            vec![
+                ("users", Position::Key),
+                ("userId", Position::Value),
+                ("nested", Position::Value),
+                ("userId", Position::Key),
+                ("key", Position::Key),
+            ]
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let buf = r#"{"userId": "x userId"}"#;
+        let positions = Format::Json.positions(buf);
+
+        assert_eq!(Position::of(&positions, 2..8), Some(Position::Key));
+        assert_eq!(Position::of(&positions, 14..20), Some(Position::Value));
+        assert_eq!(Position::of(&positions, 0..1), None);
+    }
+}
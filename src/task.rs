@@ -1,23 +1,80 @@
+use std::{
+    borrow::Cow, cell::OnceCell, cmp::Reverse, collections::HashSet, ops::Range, str::FromStr,
+};
+
 use crate::{
+    affix::{split_prefix, Affixes},
     casing::{Casing, CasingSeparator},
-    record::Records,
-    task::offset::Offset,
+    collision::{is_word_bounded, Collision},
+    conventions::Conventions,
+    dictionary::Dictionary,
+    directive,
+    lang::{InvalidRename, Lang, OnInvalid},
+    lexer::{self, Region, Syntax},
+    location::Location,
+    markdown::{self, MarkdownScope},
+    pattern::{identifiers, Pattern},
+    plural::{possessive, possessive_len},
+    range,
+    record::{Record, Records},
+    skipped::{find_case_insensitive, SkipReason, Skipped},
+    structured::Position,
+    task::{candidate::Candidate, offset::Offset},
+    template::Template,
     token::{Token, TokenError},
     Error, Result,
 };
 
+mod candidate;
 mod offset;
+mod options;
+
+pub use options::{LowerAs, Occurrence, Options, Resolution, UpperAs};
+
+/// Separates several spellings of the candidate term, as in `user|usr`
+pub const CANDIDATE_SEPARATOR: char = '|';
+
+/// The rename term, or a template wrapping the words of each match
+#[derive(Debug)]
+enum Rename {
+    Term(Token),
+    Template(Template),
+}
 
 #[derive(Debug)]
 pub struct Task<'a> {
-    candidate: Token,
-    rename: Token,
+    candidates: Vec<Candidate>,
+    pattern: Option<Pattern>,
+    rename: Rename,
+    rename_affixes: Affixes,
+    rename_prefix: Option<String>,
     preferred_casing_separator: CasingSeparator,
+    casings: Vec<Casing>,
+    options: Options,
+    file_conventions: OnceCell<Conventions>,
+    regions: OnceCell<Vec<(Range<usize>, Region)>>,
+    markdown_code: OnceCell<Vec<Range<usize>>>,
+    positions: OnceCell<Vec<(Range<usize>, Position)>>,
+    ignored: OnceCell<Vec<Range<usize>>>,
+    marked: OnceCell<Vec<Range<usize>>>,
+    line_starts: OnceCell<Vec<usize>>,
+    /// Occurrences left out by the occurrence selection, which aren't near misses
+    unselected: Records,
     buf: &'a str,
 }
 
 impl<'a> Task<'a> {
+    /// Several spellings of the candidate can be given separated by `|`, e.g. `user|usr`
     pub fn build(candidate: &str, rename: &str, buf: &'a str) -> Result<Self> {
+        Task::build_with_options(candidate, rename, buf, Options::default())
+    }
+
+    pub fn build_with_options(
+        candidate: &str,
+        rename: &str,
+        buf: &'a str,
+        options: Options,
+    ) -> Result<Self> {
         let candidate_err = |e: Error| {
             let reason = match e {
                 Error::Custom(reason) => reason,
@@ -36,63 +93,720 @@ impl<'a> Task<'a> {
             Error::RenameCasing(reason)
         };
 
-        Ok(Self {
-            candidate: Token::from_str(candidate).map_err(candidate_err)?,
-            rename: Token::from_str(rename).map_err(rename_err)?,
-            preferred_casing_separator: Casing::detect_casing(rename)?.into(),
+        let dictionary = options.flat.then(|| match &options.dictionary {
+            Some(dictionary) => Cow::Borrowed(dictionary),
+            None => Cow::Owned(Dictionary::bundled()),
+        });
+
+        let mut candidates = vec![];
+        let mut pattern = None;
+
+        if options.regex {
+            pattern = Some(Pattern::parse(candidate)?);
+        }
+
+        for candidate in candidate
+            .split(CANDIDATE_SEPARATOR)
+            .filter(|_| !options.regex)
+        {
+            let (affixes, candidate) = Affixes::split(candidate);
+            let (prefix, candidate) = match split_prefix(candidate, &options.prefixes) {
+                Some((prefix, rest)) => (Some(prefix.to_string()), rest),
+                None => (None, candidate),
+            };
+
+            let mut token = Token::from_str(candidate).map_err(candidate_err)?;
+
+            if let Some(dictionary) = &dictionary {
+                token = token.segmented(dictionary);
+            }
+
+            let token = token.with_digit_policy(options.digits);
+            // NOTE: a plural spelled like the singular, as in "sheep", is read as the singular
+            let plural = options
+                .plural
+                .then(|| token.to_plural(&options.irregulars))
+                .filter(|plural| *plural != token);
+
+            candidates.push(Candidate {
+                token,
+                affixes,
+                prefix,
+                plural,
+            });
+        }
+
+        let (rename_affixes, rename) = Affixes::split(rename);
+
+        // NOTE: the rename only loses its prefix when a candidate has one, otherwise
+        // "user" -> "IAccount" would drop the "I"
+        let (rename_prefix, rename) = match split_prefix(rename, &options.prefixes) {
+            Some((prefix, rest)) if candidates.iter().any(|c| c.prefix.is_some()) => {
+                (Some(prefix.to_string()), rest)
+            }
+            _ => (None, rename),
+        };
+
+        let finish = |mut token: Token| {
+            if let Some(dictionary) = &dictionary {
+                token = token.segmented(dictionary);
+            }
+
+            token
+                .with_digit_policy(options.digits)
+                .with_acronyms(&options.acronyms)
+        };
+
+        let rename_term = match Template::parse(rename).map_err(rename_err)? {
+            Some(template) => {
+                let groups = pattern.as_ref().map_or(0, Pattern::groups);
+
+                if template.max_group().is_some_and(|n| n > groups) {
+                    return Err(Error::RenameCasing(format!(
+                        "The rename refers to a group the candidate doesn't have ({groups} groups)"
+                    )));
+                }
+
+                Rename::Template(template.map(finish))
+            }
+            None => Rename::Term(finish(Token::from_str(rename).map_err(rename_err)?)),
+        };
+
+        let mut task = Self {
+            candidates,
+            pattern,
+            rename: rename_term,
+            rename_affixes,
+            rename_prefix,
+            preferred_casing_separator: Casing::detect_casing(&Template::sample(rename))?.into(),
+            casings: vec![],
+            options,
+            file_conventions: OnceCell::new(),
+            regions: OnceCell::new(),
+            markdown_code: OnceCell::new(),
+            positions: OnceCell::new(),
+            ignored: OnceCell::new(),
+            marked: OnceCell::new(),
+            line_starts: OnceCell::new(),
+            unselected: Records::new(),
             buf,
-        })
+        };
+        task.set_casings(&Task::casings_for(&task.options));
+
+        Ok(task)
+    }
+
+    /// Casings searched by [`Task::generate_records`]
+    pub fn casings(&self) -> &[Casing] {
+        &self.casings
+    }
+
+    pub fn set_casings(&mut self, casings: &[Casing]) {
+        self.casings.clear();
+
+        for casing in casings {
+            if !self.casings.contains(casing) {
+                self.casings.push(casing.clone());
+            }
+        }
+    }
+
+    fn casings_for(options: &Options) -> Vec<Casing> {
+        let mut casings = match &options.only_casings {
+            Some(only) => only.clone(),
+            None => {
+                let mut casings = Casing::IDENTIFIERS.to_vec();
+
+                // NOTE: prose casings are opt-in since they produce false positives in code
+                // Example: "user" in "supply user" is a lowercase match on its own
+                if options.prose || options.markdown_prose {
+                    casings.extend(Casing::PROSE);
+                }
+
+                casings.extend(options.extra_casings.iter().cloned());
+                casings
+            }
+        };
+
+        casings.retain(|casing| !options.skip_casings.contains(casing));
+        casings
     }
 
     pub fn generate_records(&mut self) -> Records {
-        let mut records = Records::new();
+        self.unselected = Records::new();
+
+        let mut records = match &self.pattern {
+            Some(pattern) => self.pattern_records(pattern),
+            None => self.candidate_records(),
+        };
+
+        self.retain_scoped(&mut records);
+        self.unselected = self.select_occurrences(&mut records);
+        self.mark_possessives(&mut records);
+        records
+    }
+
+    /// Extends plural records over the possessive marker that follows them, when asked to
+    fn mark_possessives(&self, records: &mut Records) {
+        if !self.options.possessive {
+            return;
+        }
+
+        for record in records.map.values_mut().filter(|record| record.plural) {
+            record.possessive = possessive_len(self.buf, record.pos, record.pos + record.len);
+        }
+    }
 
-        // collection of casings to operate on
-        let casings: Vec<_> = vec![
-            Casing::Lower,
-            Casing::Pascal,
-            Casing::Camel,
-            Casing::Snake,
-            Casing::Upper,
-            Casing::UpperSnake,
-            Casing::Kebab,
-            Casing::UpperKebab,
-        ];
-
-        let casified_candidates: Vec<_> = casings
+    /// Keeps the occurrences selected by the skip count, occurrence and max count options,
+    /// in the order they appear, and returns the others
+    fn select_occurrences(&self, records: &mut Records) -> Records {
+        let Options {
+            skip,
+            occurrence,
+            max_count,
+            ..
+        } = self.options;
+
+        let mut remaining = records.map.keys().copied().skip(skip);
+        let mut selected: Vec<usize> = match occurrence {
+            Occurrence::All => remaining.collect(),
+            Occurrence::Nth(n) => remaining.nth(n.get() - 1).into_iter().collect(),
+            Occurrence::Last => remaining.last().into_iter().collect(),
+        };
+
+        if let Some(max_count) = max_count {
+            selected.truncate(max_count);
+        }
+
+        let selected: HashSet<usize> = selected.into_iter().collect();
+        let (kept, unselected) = std::mem::take(&mut records.map)
+            .into_iter()
+            .partition(|(pos, _)| selected.contains(pos));
+
+        records.map = kept;
+        Records { map: unselected }
+    }
+
+    /// Drops the records on lines ignored by directives and those outside the selected lines,
+    /// byte ranges, marked regions, code, comments, strings, Markdown sections or data file
+    /// positions
+    fn retain_scoped(&self, records: &mut Records) {
+        records.map.retain(|pos, record| {
+            let is_prose_in_code = self.options.markdown_prose
+                && Casing::PROSE.contains(&record.casing)
+                && self.in_markdown_code(*pos);
+
+            self.in_scope(*pos, record.len) && !is_prose_in_code
+        });
+    }
+
+    fn in_scope(&self, pos: usize, len: usize) -> bool {
+        !self.is_ignored(pos)
+            && self.in_selection(pos, len)
+            && self.passes_filters(pos, len)
+            && self.in_marked_region(pos)
+            && self.in_region_scope(pos)
+            && self.in_markdown_scope(pos)
+            && self.in_structured_scope(pos, len)
+    }
+
+    /// Whether `pos` is on a line left out by an `altr:ignore-line`, `altr:ignore-next-line`
+    /// or `altr:off` directive
+    fn is_ignored(&self, pos: usize) -> bool {
+        if self.options.no_directives {
+            return false;
+        }
+
+        let ignored = self
+            .ignored
+            .get_or_init(|| directive::ignored_lines(self.buf));
+
+        range::contains(ignored, pos)
+    }
+
+    /// Whether `pos..pos + len` is within one of the lines or byte ranges selected in the
+    /// options, when there are any
+    fn in_selection(&self, pos: usize, len: usize) -> bool {
+        let Options {
+            lines, byte_ranges, ..
+        } = &self.options;
+
+        if lines.is_empty() && byte_ranges.is_empty() {
+            return true;
+        }
+
+        let line_starts = self
+            .line_starts
+            .get_or_init(|| Location::line_starts(self.buf));
+        let line = line_starts.partition_point(|start| *start <= pos);
+
+        lines.iter().any(|range| range.contains(&line))
+            || byte_ranges
+                .iter()
+                .any(|range| range.start <= pos && pos + len <= range.end)
+    }
+
+    /// Whether the line of `pos..pos + len` and the text around it pass the context filters
+    fn passes_filters(&self, pos: usize, len: usize) -> bool {
+        if self.options.filters.is_empty() {
+            return true;
+        }
+
+        let start = self.buf[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.buf[pos..]
+            .find('\n')
+            .map_or(self.buf.len(), |i| pos + i);
+        let line = self.buf[start..end].trim_end_matches('\r');
+        let end = start + line.len().max(pos + len - start);
+
+        let before = &self.buf[start..pos];
+        let after = &self.buf[pos + len..end];
+
+        self.options
+            .filters
             .iter()
-            .map(|casing| self.candidate.try_to_casing(casing))
-            .collect();
+            .all(|filter| filter.keeps(line, before, after))
+    }
+
+    /// Whether `pos` is between `altr:begin` and `altr:end` markers, when only marked regions
+    /// are renamed
+    fn in_marked_region(&self, pos: usize) -> bool {
+        if !self.options.marked {
+            return true;
+        }
+
+        let marked = self
+            .marked
+            .get_or_init(|| directive::marked_lines(self.buf));
+
+        range::contains(marked, pos)
+    }
+
+    /// Whether `pos` is in the code, comments or strings selected in the options
+    fn in_region_scope(&self, pos: usize) -> bool {
+        let Some(only) = self.options.only else {
+            return true;
+        };
+
+        self.region_at(pos) == only
+    }
+
+    /// Whether `pos` is in code, a comment or a string, by the syntax given in the options
+    fn region_at(&self, pos: usize) -> Region {
+        let regions = self.regions.get_or_init(|| {
+            let syntax = self
+                .options
+                .syntax
+                .or(self.options.lang.map(Syntax::from))
+                .unwrap_or(Syntax::CLike);
+            let lifetimes = self.options.lang == Some(Lang::Rust);
+
+            lexer::regions(self.buf, syntax, lifetimes)
+        });
+
+        Region::at(regions, pos)
+    }
+
+    fn in_markdown_scope(&self, pos: usize) -> bool {
+        match self.options.markdown_scope {
+            Some(MarkdownScope::Code) => self.in_markdown_code(pos),
+            Some(MarkdownScope::Prose) => !self.in_markdown_code(pos),
+            None => true,
+        }
+    }
+
+    /// Whether `pos..pos + len` lies within a key, or a value when selected, of a data file
+    fn in_structured_scope(&self, pos: usize, len: usize) -> bool {
+        let Some(format) = self.options.structured else {
+            return true;
+        };
+
+        let positions = self.positions.get_or_init(|| format.positions(self.buf));
+
+        Position::of(positions, pos..pos + len).is_some_and(|position| {
+            match self.options.positions.as_slice() {
+                [] => position == Position::Key,
+                selected => selected.contains(&position),
+            }
+        })
+    }
+
+    /// Whether `pos` is in a fenced code block or an inline code span of a Markdown input
+    fn in_markdown_code(&self, pos: usize) -> bool {
+        let ranges = self
+            .markdown_code
+            .get_or_init(|| markdown::code_ranges(self.buf));
+
+        range::contains(ranges, pos)
+    }
+
+    fn candidate_records(&self) -> Records {
+        let mut records = Records::new();
+
+        let mut matches = vec![];
+
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let mut patterns: Vec<(Casing, String, bool)> = vec![];
+
+            // NOTE: plural patterns go first so that "users" is recorded rather than "user"
+            if let Some(plural) = &candidate.plural {
+                for (casing, pattern) in self.patterns_for(candidate, plural) {
+                    patterns.push((casing, pattern, true));
+                }
+            }
+
+            for (casing, pattern) in self.patterns_for(candidate, &candidate.token) {
+                patterns.push((casing, pattern, false));
+            }
+
+            let mut line_offset: usize = 0;
+
+            // NOTE: lines keep their line break so that offsets stay right with \r\n endings
+            for line in self.buf.split_inclusive('\n') {
+                for (casing, pattern, plural) in patterns.iter() {
+                    for (start, _) in line.match_indices(pattern.as_str()) {
+                        let end = start + pattern.len();
+
+                        // NOTE: occurrences keep their own affixes unless they carry exactly those of
+                        // the candidate, in which case they take the affixes of the rename
+                        // Example: "_user" -> "__account" turns "_user" into "__account" and "__user" into "__account"
+                        let swapped = Affixes::around(line, start, end).filter(|affixes| {
+                            !(candidate.affixes.is_empty() && self.rename_affixes.is_empty())
+                                && *affixes == candidate.affixes
+                        });
+
+                        let record = match swapped {
+                            Some(affixes) => Record {
+                                swap_affixes: true,
+                                plural: *plural,
+                                candidate: index,
+                                ..Record::new(
+                                    line_offset + start - affixes.prefix.len(),
+                                    pattern.len() + affixes.len(),
+                                    casing.clone(),
+                                )
+                            },
+                            None => Record {
+                                plural: *plural,
+                                candidate: index,
+                                ..Record::new(line_offset + start, pattern.len(), casing.clone())
+                            },
+                        };
+
+                        matches.push(record);
+                    }
+                }
+
+                line_offset += line.len();
+            }
+        }
+
+        // NOTE: matches are merged leftmost-longest, the first pattern winning a tie
+        // Example: "user|user_info" records "user_info" whole rather than "user" followed by "_info"
+        matches.sort_by_key(|record| (record.pos, Reverse(record.len)));
+
+        for record in matches {
+            if !records.overlaps(record.pos, record.len) {
+                let _ = records.try_insert_record(record);
+            }
+        }
+
+        if self.options.force_mixed {
+            for skipped in self.skipped(&records) {
+                let text = &self.buf[skipped.pos..skipped.pos + skipped.len];
+
+                let _ = records.try_insert_record(Record {
+                    mixed: true,
+                    ..Record::new(
+                        skipped.pos,
+                        skipped.len,
+                        Casing::detect_casing(text).unwrap_or(Casing::Lower),
+                    )
+                });
+            }
+        }
+
+        records
+    }
+
+    /// Records of the identifiers matching a regex candidate in one of the searched casings
+    fn pattern_records(&self, pattern: &Pattern) -> Records {
+        let mut records = Records::new();
+
+        for (pos, identifier) in identifiers(self.buf) {
+            // NOTE: hyphenated runs are tried whole first, then hyphen by hyphen since
+            // they're as likely to be subtractions as kebab-case identifiers
+            // Example: "user-profile-dto" is a single match but "a-userProfileDto" isn't
+            let mut parts = vec![(pos, identifier)];
+            parts.extend(
+                identifier
+                    .split('-')
+                    .scan(pos, |start, part| {
+                        let found = (*start, part);
+                        *start += part.len() + 1;
+                        Some(found)
+                    })
+                    .filter(|_| identifier.contains('-')),
+            );
+
+            for (pos, part) in parts {
+                let (affixes, core) = Affixes::split(part);
+                let pos = pos + affixes.prefix.len();
+
+                let Ok(casing) = Casing::detect_casing(core) else {
+                    continue;
+                };
+
+                if records.overlaps(pos, core.len()) || !self.casings.contains(&casing) {
+                    continue;
+                }
+
+                if let Some(groups) = pattern.captures(core) {
+                    let _ = records.try_insert_record(Record {
+                        groups,
+                        ..Record::new(pos, core.len(), casing)
+                    });
+                }
+            }
+        }
+
+        records
+    }
 
-        let casing_with_candidates: Vec<(&Casing, &String)> = casings
+    /// Search patterns for `token`, a form of `candidate`, along with the casing each of them
+    /// stands for
+    fn patterns_for(&self, candidate: &Candidate, token: &Token) -> Vec<(Casing, String)> {
+        let mut casing_with_candidates: Vec<(Casing, String)> = self
+            .casings
             .iter()
-            .zip(casified_candidates.iter())
             // NOTE: Ambiguity errors are noop matching cases since those will be automatically
             // handled by token conversion to cases like camelCase or UpperSnakeCase
             // Example: "user" is the same in both camelCase and lowercase, hence we ignore the lowercase
             // ambiguity error here
             // As a side-effect, pure lowercase/uppercase matches will be ignored
             // Example: "myUser" candidate will not altr "myuser"
-            .filter(|(_, p)| p.is_ok())
-            .map(|(c, p)| (c, p.as_ref().expect("fitering Ok values")))
+            .filter_map(|casing| {
+                let pattern = token.try_to_casing(casing).ok()?;
+                Some((casing.clone(), pattern))
+            })
             .collect();
 
-        let mut line_offset: usize = 0;
+        // NOTE: flat mode brings back the pure lowercase/uppercase matches of multi-word candidates
+        if self.is_flat(candidate) && self.casings.contains(&Casing::Lower) {
+            casing_with_candidates.push((Casing::Lower, token.to_flat_case()));
+        }
+        if self.is_flat(candidate) && self.casings.contains(&Casing::Upper) {
+            casing_with_candidates.push((Casing::Upper, token.to_upper_flat_case()));
+        }
+
+        // NOTE: a prefixed candidate only exists as its prefix followed by PascalCase words
+        // Example: "IUser" is searched as "IUser" but not as "i_user" or "user"
+        if let Some(prefix) = &candidate.prefix {
+            casing_with_candidates.retain(|(casing, _)| *casing == Casing::Pascal);
+
+            for (casing, pattern) in casing_with_candidates.iter_mut() {
+                pattern.insert_str(0, prefix);
+
+                // without a prefix of its own the rename takes the casing of the whole identifier
+                // Example: "kUserCount" -> "maxUsers" is camelCase rather than "MaxUsers"
+                if self.rename_prefix.is_none() {
+                    *casing = Casing::detect_casing(pattern).unwrap_or(Casing::Pascal);
+                }
+            }
+        }
+
+        casing_with_candidates
+    }
+
+    /// Occurrences of the candidate that match it case-insensitively but aren't covered by
+    /// `records`, i.e. are left alone because of an odd, mixed or unsearched casing
+    pub fn skipped(&self, records: &Records) -> Vec<Skipped> {
+        let mut found = Records::new();
+        let mut skipped = vec![];
+
+        for candidate in self.candidates.iter() {
+            let prefix = candidate.prefix.as_deref().unwrap_or("").to_lowercase();
+            let mut patterns = vec![prefix.clone() + &candidate.token.to_flat_case()];
+
+            for casing in &Casing::ALL {
+                if let Ok(pattern) = candidate.token.try_to_casing(casing) {
+                    let pattern = prefix.clone() + &pattern.to_lowercase();
+
+                    if !patterns.contains(&pattern) {
+                        patterns.push(pattern);
+                    }
+                }
+            }
+
+            for pattern in patterns {
+                for (pos, len) in find_case_insensitive(self.buf, &pattern) {
+                    // occurrences out of scope or not selected are left alone on purpose
+                    if records.overlaps(pos, len)
+                        || self.unselected.overlaps(pos, len)
+                        || found.overlaps(pos, len)
+                        || !self.in_scope(pos, len)
+                    {
+                        continue;
+                    }
+
+                    let _ = found.try_insert(pos, len, Casing::Lower);
+                    skipped.push(Skipped {
+                        pos,
+                        len,
+                        location: Location::of(self.buf, pos),
+                        reason: Task::skip_reason(candidate, &self.buf[pos..pos + len]),
+                    });
+                }
+            }
+        }
+
+        skipped.sort_by_key(|s| s.pos);
+        skipped
+    }
+
+    /// Occurrences of the rename term in any casing that already exist in the input, leaving out
+    /// those that are part of `records` since they get renamed themselves.
+    /// Nothing collides when there's nothing to rename
+    pub fn collisions(&self, records: &Records) -> Vec<Collision> {
+        if records.map.is_empty() {
+            return vec![];
+        }
+
+        let mut tokens = match &self.rename {
+            Rename::Term(token) => vec![token.clone()],
+            Rename::Template(template) => self
+                .candidates
+                .iter()
+                .map(|candidate| {
+                    template
+                        .expand(&candidate.token, &[])
+                        .with_acronyms(&self.options.acronyms)
+                })
+                .collect(),
+        };
+        tokens.extend(
+            records
+                .iter()
+                .map(|(_, record)| self.rename_token_for(record)),
+        );
+
+        let mut patterns: Vec<String> = vec![];
+
+        for token in tokens.iter() {
+            for casing in &Casing::ALL {
+                if let Ok(pattern) = token.try_to_casing(casing) {
+                    if !patterns.contains(&pattern) {
+                        patterns.push(pattern);
+                    }
+                }
+            }
+        }
+
+        // NOTE: longer patterns go first so that "ACCOUNT_ID" is reported rather than "ACCOUNT"
+        patterns.sort_by_key(|pattern| Reverse(pattern.len()));
+
+        let mut found = Records::new();
+        let mut collisions = vec![];
 
-        for line in self.buf.lines() {
-            for (casing, pattern) in casing_with_candidates.iter() {
-                let matches = line.match_indices(*pattern);
+        for pattern in patterns {
+            for (pos, _) in self.buf.match_indices(pattern.as_str()) {
+                let len = pattern.len();
 
-                for item in matches {
-                    let _ =
-                        records.try_insert(item.0 + line_offset, pattern.len(), (**casing).clone());
+                if records.overlaps(pos, len)
+                    || found.overlaps(pos, len)
+                    || !is_word_bounded(self.buf, pos, len)
+                {
+                    continue;
                 }
+
+                let _ = found.try_insert(pos, len, Casing::Lower);
+                collisions.push(Collision {
+                    pos,
+                    len,
+                    location: Location::of(self.buf, pos),
+                });
             }
+        }
+
+        collisions.sort_by_key(|c| c.pos);
+        collisions
+    }
 
-            line_offset += line.len() + 1; // + 1 accounts for the \n character
+    /// Fails with the [`collisions`](Task::collisions) found unless they are allowed
+    pub fn check_collisions(&self, records: &Records) -> Result<()> {
+        let collisions = self.collisions(records);
+
+        if collisions.is_empty() || self.options.allow_collisions {
+            return Ok(());
         }
 
+        Err(Error::Collisions(collisions))
+    }
+
+    /// Renames that turn a valid identifier into a keyword or an invalid identifier in the
+    /// language set in the options
+    /// Example: "kind" -> "type" in Rust, or "user" -> "old-user" anywhere
+    pub fn invalid_renames(&self, records: &Records) -> Vec<InvalidRename> {
+        let Some(lang) = self.options.lang else {
+            return vec![];
+        };
+
         records
+            .iter()
+            .filter_map(|(_, record)| {
+                // occurrences in strings and comments aren't identifiers
+                if self.region_at(record.pos) != Region::Code {
+                    return None;
+                }
+
+                let (start, end) = lang.identifier_around(self.buf, record.pos, record.len);
+
+                // nor are those that weren't valid identifiers to begin with
+                if lang.check(&self.buf[start..end]).is_some() {
+                    return None;
+                }
+
+                let identifier = format!(
+                    "{}{}{}",
+                    &self.buf[start..record.pos],
+                    self.rename_for(record),
+                    &self.buf[record.pos + record.len..end]
+                );
+                let problem = lang.check(&identifier)?;
+
+                Some(InvalidRename {
+                    pos: start,
+                    len: end - start,
+                    location: Location::of(self.buf, start),
+                    identifier,
+                    problem,
+                })
+            })
+            .collect()
+    }
+
+    /// The [`invalid_renames`](Task::invalid_renames) to warn about, or an error with them
+    /// when they aren't tolerated
+    pub fn check_renames(&self, records: &Records) -> Result<Vec<InvalidRename>> {
+        let invalid = self.invalid_renames(records);
+
+        if invalid.is_empty() || self.options.on_invalid == OnInvalid::Warn {
+            return Ok(invalid);
+        }
+
+        Err(Error::InvalidRenames(invalid))
+    }
+
+    fn skip_reason(candidate: &Candidate, text: &str) -> SkipReason {
+        let Ok(casing) = Casing::detect_casing(text) else {
+            return SkipReason::MixedCasing;
+        };
+
+        match candidate.token.try_to_casing(&casing) {
+            Ok(pattern) if pattern == text => SkipReason::NotSearched(casing),
+            Err(_) if matches!(casing, Casing::Lower | Casing::Upper) => SkipReason::Flat,
+            _ => SkipReason::MixedCasing,
+        }
     }
 
     pub fn process_records(&mut self, records: &mut Records) -> (String, Records) {
@@ -102,66 +816,253 @@ impl<'a> Task<'a> {
         let mut processed_records = Records::new();
 
         for (_, record) in records.iter() {
-            let rename =
-                self.rename
-                    .try_to_casing(&record.casing)
-                    .unwrap_or_else(|err| match err {
-                        TokenError::AmbiguousToLowerCase => match self.preferred_casing_separator {
-                            CasingSeparator::None => self.rename.to_camel_case(),
-                            CasingSeparator::Underscore => self.rename.to_snake_case(),
-                            CasingSeparator::Hyphen => self.rename.to_kebab_case(),
-                        },
-                        TokenError::AmbiguousToUpperCase => match self.preferred_casing_separator {
-                            CasingSeparator::None => self.rename.to_upper_snake_case(),
-                            CasingSeparator::Underscore => self.rename.to_upper_snake_case(),
-                            CasingSeparator::Hyphen => self.rename.to_upper_kebab_case(),
-                        },
-                    });
+            let mut rename = self.rename_for(record);
+            let mut len = record.len;
+
+            if let Some(marker_len) = record.possessive {
+                let end = record.pos + record.len;
+                rename += &possessive(&rename, &self.buf[end..end + marker_len]);
+                len += marker_len;
+            }
 
             let start = offset.apply(record.pos);
-            let end = offset.apply(record.pos + record.len);
+            let end = offset.apply(record.pos + len);
 
             buf.replace_range(start..end, &rename);
-            let _ = processed_records.try_insert(start, rename.len(), record.casing.clone());
+            let _ = processed_records.try_insert_record(Record {
+                pos: start,
+                len: rename.len(),
+                ..record.clone()
+            });
 
-            offset = Offset::add(offset, Offset::from_diff(rename.len(), record.len));
+            offset = Offset::add(offset, Offset::from_diff(rename.len(), len));
         }
 
         (buf, processed_records)
     }
-}
 
-#[cfg(test)]
-mod test_task {
+    /// Renders the rename for a record of the given casing
+    fn rename_for(&self, record: &Record) -> String {
+        let mut rename = self.rename_core_for(record);
 
-    use super::*;
+        let is_prefixed = self
+            .candidates
+            .get(record.candidate)
+            .is_some_and(|candidate| candidate.prefix.is_some());
 
-    fn assert_expected<'a>(candidate: &'a str, rename: &'a str, input: &'a str, expected: &'a str) {
-        let mut task = Task::build(candidate, rename, input).unwrap();
+        if is_prefixed && !record.mixed {
+            rename.insert_str(0, self.rename_prefix.as_deref().unwrap_or(""));
+        }
 
-        let mut records = task.generate_records();
-        let (result, _) = task.process_records(&mut records);
+        if record.swap_affixes {
+            let Affixes { prefix, suffix } = &self.rename_affixes;
+            rename = format!("{prefix}{rename}{suffix}");
+        }
 
-        assert_eq!(result, expected, "Result: {}", result);
+        self.escaped(record, rename)
     }
 
-    #[test]
-    fn test_1() {
-        let candidate = "user";
-        let rename = "supplyUser";
+    /// `rename` as a raw identifier when it replaces a whole identifier in code with a Rust keyword
+    fn escaped(&self, record: &Record, rename: String) -> String {
+        let Some(lang) = self.options.lang.filter(|_| self.options.raw_identifiers) else {
+            return rename;
+        };
 
-        let input = r"user User USER";
-        let expected = r"supplyUser SupplyUser SUPPLY_USER";
+        if self.region_at(record.pos) != Region::Code {
+            return rename;
+        }
 
-        assert_expected(candidate, rename, input, expected);
+        let bounds = lang.identifier_around(self.buf, record.pos, record.len);
+
+        if bounds != (record.pos, record.pos + record.len) {
+            return rename;
+        }
+
+        lang.escape(&rename).unwrap_or(rename)
     }
 
-    #[test]
-    fn test_2() {
-        let candidate = "user";
-        let rename = "dayTrader";
+    /// The words of the rename for a record, before they are rendered in its casing
+    fn rename_token_for(&self, record: &Record) -> Token {
+        let rename = match &self.rename {
+            Rename::Term(token) => token.clone(),
+            Rename::Template(template) => {
+                let matched = match record.groups.first() {
+                    Some(matched) => matched,
+                    None => &self.candidates[record.candidate].token,
+                };
 
-        let input = r#"
+                template
+                    .expand(matched, &record.groups)
+                    .with_acronyms(&self.options.acronyms)
+            }
+        };
+
+        if record.plural {
+            rename.to_plural(&self.options.irregulars)
+        } else {
+            rename
+        }
+    }
+
+    fn rename_core_for(&self, record: &Record) -> String {
+        let rename = &self.rename_token_for(record);
+
+        if record.mixed {
+            let original = &self.buf[record.pos..record.pos + record.len];
+            return rename.to_mixed_case(original);
+        }
+
+        let casing = &record.casing;
+        let resolved = match rename.try_to_casing(casing) {
+            Ok(_) => casing.clone(),
+            Err(err) => self.resolve_ambiguity(err, record),
+        };
+
+        // NOTE: overrides apply to the casing of the match as well as the casing an ambiguous
+        // match resolves to
+        // Example: "RESULT" is an uppercase match rendered in UPPER_SNAKE_CASE for "parsed_transaction"
+        // Plural matches are never overridden since the overrides are singular
+        let overridden = self
+            .options
+            .overrides
+            .iter()
+            .find(|(c, _)| c == casing)
+            .or_else(|| self.options.overrides.iter().find(|(c, _)| *c == resolved))
+            .filter(|_| !record.plural);
+
+        if let Some((_, rename)) = overridden {
+            return rename.clone();
+        }
+
+        // lowercase and uppercase only fail to render here when the rename is meant to be flat
+        rename
+            .try_to_casing(&resolved)
+            .unwrap_or_else(|err| match err {
+                TokenError::AmbiguousToLowerCase => rename.to_flat_case(),
+                TokenError::AmbiguousToUpperCase => rename.to_upper_flat_case(),
+            })
+    }
+
+    /// Picks the casing a multi-word rename is rendered in for a single-word match
+    fn resolve_ambiguity(&self, err: TokenError, record: &Record) -> Casing {
+        let pos = record.pos;
+        let is_flat = self
+            .candidates
+            .get(record.candidate)
+            .is_some_and(|candidate| self.is_flat(candidate));
+
+        match err {
+            TokenError::AmbiguousToLowerCase if is_flat => Casing::Lower,
+            TokenError::AmbiguousToUpperCase if is_flat => Casing::Upper,
+            TokenError::AmbiguousToLowerCase => match self.options.lower_as {
+                Some(LowerAs::Snake) => Casing::Snake,
+                Some(LowerAs::Kebab) => Casing::Kebab,
+                Some(LowerAs::Camel) => Casing::Camel,
+                Some(LowerAs::Flat) => Casing::Lower,
+                None => self
+                    .context_casing(pos, Conventions::dominant_lower)
+                    .unwrap_or(match self.preferred_casing_separator {
+                        CasingSeparator::None => Casing::Camel,
+                        CasingSeparator::Underscore => Casing::Snake,
+                        CasingSeparator::Hyphen => Casing::Kebab,
+                        CasingSeparator::Space => Casing::Space,
+                        CasingSeparator::Dot => Casing::Dot,
+                        CasingSeparator::Slash => Casing::Path,
+                    }),
+            },
+            TokenError::AmbiguousToUpperCase => match self.options.upper_as {
+                Some(UpperAs::UpperSnake) => Casing::UpperSnake,
+                Some(UpperAs::UpperKebab) => Casing::UpperKebab,
+                Some(UpperAs::Flat) => Casing::Upper,
+                None => self
+                    .context_casing(pos, Conventions::dominant_upper)
+                    .unwrap_or(match self.preferred_casing_separator {
+                        CasingSeparator::Hyphen => Casing::UpperKebab,
+                        _ => Casing::UpperSnake,
+                    }),
+            },
+        }
+    }
+
+    /// The dominant identifier style around `pos` in context resolution mode,
+    /// looking at its line first and at the whole input otherwise
+    fn context_casing(
+        &self,
+        pos: usize,
+        dominant: fn(&Conventions) -> Option<Casing>,
+    ) -> Option<Casing> {
+        if self.options.resolve_ambiguity != Resolution::Context {
+            return None;
+        }
+
+        let start = self.buf[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.buf[pos..]
+            .find('\n')
+            .map_or(self.buf.len(), |i| pos + i);
+
+        dominant(&Conventions::scan(&self.buf[start..end])).or_else(|| {
+            dominant(
+                self.file_conventions
+                    .get_or_init(|| Conventions::scan(self.buf)),
+            )
+        })
+    }
+
+    /// Lowercase and uppercase records are flat forms of a multi-word candidate in flat mode
+    fn is_flat(&self, candidate: &Candidate) -> bool {
+        self.options.flat && candidate.token.is_multi_word()
+    }
+}
+
+#[cfg(test)]
+mod test_task {
+
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::{
+        filter::Filter,
+        lang::{Lang, Problem},
+        structured::Format,
+        token::DigitPolicy,
+    };
+
+    fn assert_expected<'a>(candidate: &'a str, rename: &'a str, input: &'a str, expected: &'a str) {
+        assert_expected_with(Options::default(), candidate, rename, input, expected);
+    }
+
+    fn assert_expected_with<'a>(
+        options: Options,
+        candidate: &'a str,
+        rename: &'a str,
+        input: &'a str,
+        expected: &'a str,
+    ) {
+        let mut task = Task::build_with_options(candidate, rename, input, options).unwrap();
+
+        let mut records = task.generate_records();
+        let (result, _) = task.process_records(&mut records);
+
+        assert_eq!(result, expected, "Result: {}", result);
+    }
+
+    #[test]
+    fn test_1() {
+        let candidate = "user";
+        let rename = "supplyUser";
+
+        let input = r"user User USER";
+        let expected = r"supplyUser SupplyUser SUPPLY_USER";
+
+        assert_expected(candidate, rename, input, expected);
+    }
+
+    #[test]
+    fn test_2() {
+        let candidate = "user";
+        let rename = "dayTrader";
+
+        let input = r#"
                 const [user, setUser] = useState("");
 
                 function getUserName() {
@@ -239,6 +1140,956 @@ mod test_task {
         assert_expected(candidate, rename, input, expected);
     }
 
+    #[test]
+    fn test_acronyms() {
+        let input = r"
+            const userID = getUserID(HTTPServer);
+            user_id = USER_ID;
+        ";
+
+        assert_expected(
+            "userID",
+            "accountID",
+            input,
+            r"
+            const accountID = getAccountID(HTTPServer);
+            account_id = ACCOUNT_ID;
+        ",
+        );
+
+        let options = Options {
+            acronyms: vec![String::from("url")],
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "fetch",
+            "fetchUrl",
+            "fetch(); Fetch(); FETCH_ALL",
+            "fetchURL(); FetchURL(); FETCH_URL_ALL",
+        );
+    }
+
+    #[test]
+    fn test_digit_policy() {
+        let options = Options {
+            digits: DigitPolicy::Separate,
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "sha256Hash",
+            "md5Digest",
+            "sha256Hash = SHA_256_HASH",
+            "md5Digest = MD_5_DIGEST",
+        );
+    }
+
+    #[test]
+    fn test_prose() {
+        let input = "// Supply user and supply user: supplyUser, Supply User";
+
+        assert_expected(
+            "supplyUser",
+            "dayTrader",
+            input,
+            "// Supply user and supply user: dayTrader, Supply User",
+        );
+
+        let options = Options {
+            prose: true,
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "supplyUser",
+            "dayTrader",
+            input,
+            "// Day trader and day trader: dayTrader, Day Trader",
+        );
+    }
+
+    #[test]
+    fn test_extra_casings() {
+        let input = r"
+            headers['Content-Type'] = config.get('content.type');
+            fetch('/content/type', { contentType });
+            Content_Type := CONTENT_TYPE;
+        ";
+
+        let options = Options {
+            extra_casings: vec![Casing::Train, Casing::Dot, Casing::Path, Casing::Ada],
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "contentType",
+            "mediaKind",
+            input,
+            r"
+            headers['Media-Kind'] = config.get('media.kind');
+            fetch('/media/kind', { mediaKind });
+            Media_Kind := MEDIA_KIND;
+        ",
+        );
+
+        assert_expected(
+            "contentType",
+            "mediaKind",
+            input,
+            r"
+            headers['Content-Type'] = config.get('content.type');
+            fetch('/content/type', { mediaKind });
+            Content_Type := MEDIA_KIND;
+        ",
+        );
+    }
+
+    #[test]
+    fn test_flat() {
+        let input = "myUser my_user myuser MYUSER";
+        let options = Options {
+            flat: true,
+            ..Default::default()
+        };
+
+        let expected = "myNewUser my_new_user mynewuser MYNEWUSER";
+
+        assert_expected_with(options.clone(), "myUser", "myNewUser", input, expected);
+        assert_expected_with(options, "myuser", "mynewuser", input, expected);
+    }
+
+    #[test]
+    fn test_ambiguity_policy() {
+        let input = "result | Result | RESULT";
+        let rename = "parsed_transaction";
+
+        assert_expected_with(
+            Options {
+                lower_as: Some(LowerAs::Camel),
+                upper_as: Some(UpperAs::Flat),
+                ..Default::default()
+            },
+            "result",
+            rename,
+            input,
+            "parsedTransaction | ParsedTransaction | PARSEDTRANSACTION",
+        );
+
+        assert_expected_with(
+            Options {
+                lower_as: Some(LowerAs::Flat),
+                upper_as: Some(UpperAs::UpperKebab),
+                ..Default::default()
+            },
+            "result",
+            rename,
+            input,
+            "parsedtransaction | ParsedTransaction | PARSED-TRANSACTION",
+        );
+
+        assert_expected_with(
+            Options {
+                overrides: vec![(Casing::UpperSnake, String::from("PTX"))],
+                ..Default::default()
+            },
+            "result",
+            rename,
+            input,
+            "parsed_transaction | ParsedTransaction | PTX",
+        );
+
+        assert_expected_with(
+            Options {
+                overrides: vec![(Casing::Pascal, String::from("Ptx"))],
+                ..Default::default()
+            },
+            "result",
+            rename,
+            input,
+            "parsed_transaction | Ptx | PARSED_TRANSACTION",
+        );
+    }
+
+    #[test]
+    fn test_context_resolution() {
+        let input = r"
+            let user = load_user(user_id);
+            const user = loadUser(userId);
+            user
+            USER = MAX_USERS + MIN_USERS
+        ";
+        let options = Options {
+            resolve_ambiguity: Resolution::Context,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            options,
+            "user",
+            "supply-user",
+            input,
+            r"
+            let supply_user = load_supply_user(supply_user_id);
+            const supplyUser = loadSupplyUser(supplyUserId);
+            supply-user
+            SUPPLY_USER = MAX_SUPPLY_USERS + MIN_SUPPLY_USERS
+        ",
+        );
+    }
+
+    #[test]
+    fn test_casing_selection() {
+        let input = "const MAX_USER = max_user(maxUser);";
+
+        assert_expected_with(
+            Options {
+                only_casings: Some(vec![Casing::UpperSnake]),
+                ..Default::default()
+            },
+            "maxUser",
+            "limit",
+            input,
+            "const LIMIT = max_user(maxUser);",
+        );
+
+        assert_expected_with(
+            Options {
+                skip_casings: vec![Casing::Snake],
+                ..Default::default()
+            },
+            "maxUser",
+            "limit",
+            input,
+            "const LIMIT = max_user(limit);",
+        );
+
+        let mut task = Task::build("maxUser", "limit", input).unwrap();
+        task.set_casings(&[Casing::Snake, Casing::Camel]);
+        assert_eq!(task.casings(), &[Casing::Snake, Casing::Camel]);
+
+        let mut records = task.generate_records();
+        let (result, _) = task.process_records(&mut records);
+        assert_eq!(result, "const MAX_USER = limit(limit);");
+    }
+
+    #[test]
+    fn test_skipped() {
+        let input = "oldUser rEsult Olduser\nolduser OLD-USER";
+        let mut task = Task::build("oldUser", "myNewUser", input).unwrap();
+        let records = task.generate_records();
+
+        let skipped: Vec<_> = task
+            .skipped(&records)
+            .into_iter()
+            .map(|s| (s.location.to_string(), s.reason))
+            .collect();
+
+        assert_eq!(
+            skipped,
+            vec![
+                (String::from("1:16"), SkipReason::MixedCasing),
+                (String::from("2:1"), SkipReason::Flat),
+            ]
+        );
+
+        let options = Options {
+            force_mixed: true,
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "oldUser",
+            "myNewUser",
+            input,
+            "myNewUser rEsult Mynewuser\nmynewuser MY-NEW-USER",
+        );
+    }
+
+    #[test]
+    fn test_affixes() {
+        let input = r"
+            def __init__(self, _user, user_):
+                self._user = _user or user_
+                __user = init_user(USER)
+        ";
+
+        assert_expected(
+            "_user",
+            "account",
+            input,
+            r"
+            def __init__(self, account, account_):
+                self.account = account or account_
+                __account = init_account(ACCOUNT)
+        ",
+        );
+
+        assert_expected(
+            "user",
+            "_account",
+            input,
+            r"
+            def __init__(self, _account, account_):
+                self._account = _account or account_
+                __account = init_account(_ACCOUNT)
+        ",
+        );
+
+        assert_expected(
+            "__init__",
+            "setup",
+            input,
+            r"
+            def setup(self, _user, user_):
+                self._user = _user or user_
+                __user = setup_user(USER)
+        ",
+        );
+
+        assert_expected("$scope", "$ctx", "$scope.$apply(scope)", "$ctx.$apply(ctx)");
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let input = r"
+            interface IUserName { mUser: IUser; kUserCount: number }
+            const user = new User(iuser);
+        ";
+        let options = Options {
+            prefixes: vec!["I".into(), "m".into(), "k".into()],
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            options.clone(),
+            "user",
+            "account",
+            input,
+            r"
+            interface IAccountName { mAccount: IAccount; kAccountCount: number }
+            const account = new Account(iaccount);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "IUser",
+            "IAccount",
+            input,
+            r"
+            interface IAccountName { mUser: IAccount; kUserCount: number }
+            const user = new User(iuser);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "mUser",
+            "mOwner",
+            input,
+            r"
+            interface IUserName { mOwner: IUser; kUserCount: number }
+            const user = new User(iuser);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "kUserCount",
+            "maxUsers",
+            input,
+            r"
+            interface IUserName { mUser: IUser; maxUsers: number }
+            const user = new User(iuser);
+        ",
+        );
+    }
+
+    #[test]
+    fn test_plural() {
+        let input = r"
+            const people = getPeople(person);
+            PEOPLE_COUNT + PERSON_ID;
+            user.children.map(child => child);
+        ";
+        let options = Options {
+            plural: true,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            options.clone(),
+            "person",
+            "customer",
+            input,
+            r"
+            const customers = getCustomers(customer);
+            CUSTOMERS_COUNT + CUSTOMER_ID;
+            user.children.map(child => child);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "child",
+            "kid",
+            input,
+            r"
+            const people = getPeople(person);
+            PEOPLE_COUNT + PERSON_ID;
+            user.kids.map(kid => kid);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "user",
+            "member",
+            input,
+            r"
+            const people = getPeople(person);
+            PEOPLE_COUNT + PERSON_ID;
+            member.children.map(child => child);
+        ",
+        );
+
+        assert_expected_with(
+            options.clone(),
+            "sheep",
+            "goat",
+            "let sheep = new Sheep(); SHEEP_COUNT",
+            "let goat = new Goat(); GOAT_COUNT",
+        );
+
+        let options = Options {
+            irregulars: vec![(String::from("kid"), String::from("kiddos"))],
+            ..options
+        };
+        assert_expected_with(
+            options,
+            "child",
+            "kid",
+            input,
+            r"
+            const people = getPeople(person);
+            PEOPLE_COUNT + PERSON_ID;
+            user.kiddos.map(kid => kid);
+        ",
+        );
+    }
+
+    #[test]
+    fn test_possessive() {
+        let options = Options {
+            plural: true,
+            possessive: true,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            options.clone(),
+            "person",
+            "customer",
+            "the people's names, a person's name, PEOPLE'S",
+            "the customers' names, a customer's name, CUSTOMERS'",
+        );
+        assert_expected_with(
+            options,
+            "customer",
+            "person",
+            "the customers' names, 'customers'",
+            "the people's names, 'people'",
+        );
+    }
+
+    #[test]
+    fn test_candidate_aliases() {
+        let input = r"
+            const usr = getUser(u_info);
+            USR_ID + UserInfo + user_infos;
+        ";
+
+        assert_expected(
+            "user|usr|u_info",
+            "account",
+            input,
+            r"
+            const account = getAccount(account);
+            ACCOUNT_ID + AccountInfo + account_infos;
+        ",
+        );
+
+        // leftmost-longest: "user_info" wins over "user" whatever the order of the aliases
+        assert_expected(
+            "user|user_info",
+            "account",
+            input,
+            r"
+            const usr = getAccount(u_info);
+            USR_ID + Account + accounts;
+        ",
+        );
+    }
+
+    #[test]
+    fn test_templates() {
+        let input = r"
+            const user = new User(USER_ID);
+        ";
+
+        assert_expected(
+            "user",
+            "{}Service",
+            input,
+            r"
+            const userService = new UserService(USER_SERVICE_ID);
+        ",
+        );
+
+        assert_expected(
+            "user",
+            "legacy_{}",
+            input,
+            r"
+            const legacy_user = new LegacyUser(LEGACY_USER_ID);
+        ",
+        );
+
+        // every alias expands to its own words
+        assert_expected(
+            "Client|Server",
+            "Http{}Impl",
+            "new Client(server)",
+            "new HttpClientImpl(httpServerImpl)",
+        );
+    }
+
+    #[test]
+    fn test_regex_candidate() {
+        let input = r"
+            const userProfileDto = new UserProfileDto();
+            USER_PROFILE_DTO + user_address_dto + userDto;
+            userProfile(a-userSettingsDto);
+        ";
+        let options = Options {
+            regex: true,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            options,
+            r"user(\w+)Dto",
+            "account{1}Model",
+            input,
+            r"
+            const accountProfileModel = new AccountProfileModel();
+            ACCOUNT_PROFILE_MODEL + account_address_model + userDto;
+            userProfile(a-accountSettingsModel);
+        ",
+        );
+
+        let options = Options {
+            regex: true,
+            ..Default::default()
+        };
+        assert!(Task::build_with_options(r"user(\w+)Dto", "{2}", input, options).is_err());
+    }
+
+    #[test]
+    fn test_collisions() {
+        let input = "user + account + AccountService + ACCOUNT_ID + accountant";
+
+        let mut task = Task::build("user", "account", input).unwrap();
+        let records = task.generate_records();
+
+        let collisions: Vec<_> = task
+            .collisions(&records)
+            .iter()
+            .map(|c| &input[c.pos..c.pos + c.len])
+            .collect();
+        assert_eq!(collisions, vec!["account", "Account", "ACCOUNT"]);
+        assert!(matches!(
+            task.check_collisions(&records),
+            Err(Error::Collisions(c)) if c.len() == 3
+        ));
+
+        let options = Options {
+            allow_collisions: true,
+            ..Default::default()
+        };
+        let mut task = Task::build_with_options("user", "account", input, options).unwrap();
+        let records = task.generate_records();
+        assert!(task.check_collisions(&records).is_ok());
+
+        // occurrences that are renamed themselves don't collide
+        let mut task = Task::build("user", "userService", "user + userService").unwrap();
+        let records = task.generate_records();
+        assert_eq!(task.collisions(&records).len(), 0);
+
+        // nothing renamed, nothing to collide with
+        let mut task = Task::build("user", "account", "account only").unwrap();
+        let records = task.generate_records();
+        assert!(task.check_collisions(&records).is_ok());
+    }
+
+    #[test]
+    fn test_language_checks() {
+        let input = r#"
+            let kind = Kind::new(my_kind);
+            let r#kind = kind_of;
+        "#;
+        let build_in = |rename: &str, raw_identifiers: bool, input: &'static str| {
+            let options = Options {
+                lang: Some(Lang::Rust),
+                on_invalid: OnInvalid::Fail,
+                raw_identifiers,
+                ..Default::default()
+            };
+            Task::build_with_options("kind", rename, input, options).unwrap()
+        };
+        let build = |rename: &str, raw_identifiers: bool| build_in(rename, raw_identifiers, input);
+
+        let mut task = build("type", false);
+        let records = task.generate_records();
+        let invalid: Vec<_> = task
+            .invalid_renames(&records)
+            .into_iter()
+            .map(|i| (i.identifier, i.problem))
+            .collect();
+        assert_eq!(invalid, vec![(String::from("type"), Problem::Keyword)]);
+        assert!(task.check_renames(&records).is_err());
+
+        let mut task = build("type", true);
+        let mut records = task.generate_records();
+        assert!(task.check_renames(&records).unwrap().is_empty());
+        assert_eq!(
+            task.process_records(&mut records).0,
+            r#"
+            let r#type = Type::new(my_type);
+            let r#type = type_of;
+        "#
+        );
+
+        let mut task = build("old-kind", false);
+        let records = task.generate_records();
+        let invalid = task.invalid_renames(&records);
+        assert_eq!(invalid.len(), 4);
+        assert!(invalid
+            .iter()
+            .all(|i| i.problem == Problem::NotAnIdentifier));
+
+        // keywords in comments and strings are plain text
+        let input = "let kind = 1; // the kind\nlet s = \"kind\";";
+        let mut task = build_in("type", true, input);
+        let mut records = task.generate_records();
+        assert!(task.check_renames(&records).unwrap().is_empty());
+        assert_eq!(
+            task.process_records(&mut records).0,
+            "let r#type = 1; // the type\nlet s = \"type\";"
+        );
+
+        let mut task = build_in("self", false, "let s = \"kind\";");
+        let records = task.generate_records();
+        assert!(task.check_renames(&records).is_ok());
+
+        // swapped affixes are escaped too
+        let options = Options {
+            lang: Some(Lang::Rust),
+            on_invalid: OnInvalid::Fail,
+            raw_identifiers: true,
+            ..Default::default()
+        };
+        let mut task =
+            Task::build_with_options("_kind", "type", "let _kind = 1;", options).unwrap();
+        let mut records = task.generate_records();
+        assert!(task.check_renames(&records).unwrap().is_empty());
+        assert_eq!(task.process_records(&mut records).0, "let r#type = 1;");
+    }
+
+    #[test]
+    fn test_scopes() {
+        let input = r#"
+            // load the user
+            let user = find("user");
+        "#;
+        let only = |region: Region| Options {
+            only: Some(region),
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            only(Region::Code),
+            "user",
+            "account",
+            input,
+            r#"
+            // load the user
+            let account = find("user");
+        "#,
+        );
+        assert_expected_with(
+            only(Region::Comment),
+            "user",
+            "account",
+            input,
+            r#"
+            // load the account
+            let user = find("user");
+        "#,
+        );
+        assert_expected_with(
+            only(Region::String),
+            "user",
+            "account",
+            input,
+            r#"
+            // load the user
+            let user = find("account");
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_markdown_scope() {
+        let input = "# Supply user\n\nCall `getUser` to fetch one user:\n\n```js\nconst user = getUser();\n```\n";
+
+        assert_expected_with(
+            Options {
+                markdown_scope: Some(MarkdownScope::Code),
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "# Supply user\n\nCall `getAccount` to fetch one user:\n\n```js\nconst account = getAccount();\n```\n"
+        );
+        assert_expected_with(
+            Options {
+                markdown_scope: Some(MarkdownScope::Prose),
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "# Supply account\n\nCall `getUser` to fetch one account:\n\n```js\nconst user = getUser();\n```\n"
+        );
+
+        let input = "Supply User\n\n```\nsupply user\n```\n";
+        let options = Options {
+            markdown_prose: true,
+            ..Default::default()
+        };
+        assert_expected_with(
+            options,
+            "supply user",
+            "account",
+            input,
+            "Account\n\n```\nsupply user\n```\n",
+        );
+    }
+
+    #[test]
+    fn test_structured() {
+        let input = r#"{"userId": 1, "contact": "userId@example.com", "userIds": ["userId"]}"#;
+        let json = |positions: Vec<Position>| Options {
+            structured: Some(Format::Json),
+            positions,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            json(vec![]),
+            "userId",
+            "accountId",
+            input,
+            r#"{"accountId": 1, "contact": "userId@example.com", "accountIds": ["userId"]}"#,
+        );
+        assert_expected_with(
+            json(vec![Position::Value]),
+            "userId",
+            "accountId",
+            input,
+            r#"{"userId": 1, "contact": "accountId@example.com", "userIds": ["accountId"]}"#,
+        );
+    }
+
+    #[test]
+    fn test_directives() {
+        let input = r"
+            user(); // altr:ignore-line
+            // altr:ignore-next-line
+            export const legacyUser = user;
+            /* altr:off */
+            type OldUser = User;
+            /* altr:on */
+            user();
+        ";
+
+        assert_expected(
+            "user",
+            "account",
+            input,
+            r"
+            user(); // altr:ignore-line
+            // altr:ignore-next-line
+            export const legacyUser = user;
+            /* altr:off */
+            type OldUser = User;
+            /* altr:on */
+            account();
+        ",
+        );
+
+        let options = Options {
+            no_directives: true,
+            ..Default::default()
+        };
+        let mut task = Task::build_with_options("user", "account", input, options).unwrap();
+        let records = task.generate_records();
+        assert_eq!(records.iter().count(), 6);
+    }
+
+    #[test]
+    fn test_selection() {
+        let input = "user\r\nuser\r\n// altr:begin\r\nuser\r\n// altr:end\r\nuser";
+
+        assert_expected_with(
+            Options {
+                lines: vec![2..=2, 6..=6],
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "user\r\naccount\r\n// altr:begin\r\nuser\r\n// altr:end\r\naccount",
+        );
+        assert_expected_with(
+            Options {
+                byte_ranges: vec![0..4, 27..33],
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "account\r\nuser\r\n// altr:begin\r\naccount\r\n// altr:end\r\nuser",
+        );
+        assert_expected_with(
+            Options {
+                marked: true,
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "user\r\nuser\r\n// altr:begin\r\naccount\r\n// altr:end\r\nuser",
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let input = r"
+            import { user } from './user';
+            const user = legacy.user;
+        ";
+        let filtered = |filters: Vec<Filter>| Options {
+            filters,
+            ..Default::default()
+        };
+
+        assert_expected_with(
+            filtered(vec![Filter::where_line("import|from").unwrap()]),
+            "user",
+            "account",
+            input,
+            r"
+            import { account } from './account';
+            const user = legacy.user;
+        ",
+        );
+        assert_expected_with(
+            filtered(vec![
+                Filter::skip_line("import").unwrap(),
+                Filter::skip_before(r"legacy\.").unwrap(),
+            ]),
+            "user",
+            "account",
+            input,
+            r"
+            import { user } from './user';
+            const account = legacy.user;
+        ",
+        );
+        assert_expected_with(
+            filtered(vec![
+                Filter::before(r"[{.]\s*").unwrap(),
+                Filter::after(r"\s*[};]").unwrap(),
+            ]),
+            "user",
+            "account",
+            input,
+            r"
+            import { account } from './user';
+            const user = legacy.account;
+        ",
+        );
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let input = "user User USER user_id";
+
+        let options = Options {
+            occurrence: Occurrence::Nth(NonZeroUsize::MIN),
+            ..Default::default()
+        };
+        let mut task = Task::build_with_options("user", "account", input, options).unwrap();
+        let records = task.generate_records();
+        assert!(task.skipped(&records).is_empty());
+
+        assert_expected_with(
+            Options {
+                occurrence: Occurrence::Nth(NonZeroUsize::new(2).unwrap()),
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "user Account USER user_id",
+        );
+        assert_expected_with(
+            Options {
+                occurrence: Occurrence::Last,
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "user User USER account_id",
+        );
+        assert_expected_with(
+            Options {
+                skip: 1,
+                max_count: Some(2),
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            "user Account ACCOUNT user_id",
+        );
+        assert_expected_with(
+            Options {
+                occurrence: Occurrence::Nth(NonZeroUsize::new(5).unwrap()),
+                ..Default::default()
+            },
+            "user",
+            "account",
+            input,
+            input,
+        );
+    }
+
     #[test]
     fn test_preferred_casing() {
         assert_expected(
@@ -0,0 +1,10 @@
+use crate::{affix::Affixes, token::Token};
+
+/// One spelling of the candidate term, e.g. `usr` in `user|usr`
+#[derive(Debug)]
+pub struct Candidate {
+    pub token: Token,
+    pub affixes: Affixes,
+    pub prefix: Option<String>,
+    pub plural: Option<Token>,
+}
@@ -0,0 +1,171 @@
+use std::{
+    num::NonZeroUsize,
+    ops::{Range, RangeInclusive},
+};
+
+use crate::{
+    casing::Casing,
+    dictionary::Dictionary,
+    filter::Filter,
+    lang::{Lang, OnInvalid},
+    lexer::{Region, Syntax},
+    markdown::MarkdownScope,
+    structured::{Format, Position},
+    token::DigitPolicy,
+};
+
+/// How a multi-word rename is rendered in place of a single-word lowercase match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LowerAs {
+    Snake,
+    Kebab,
+    Camel,
+    Flat,
+}
+
+/// How a multi-word rename is rendered in place of a single-word UPPERCASE match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum UpperAs {
+    UpperSnake,
+    UpperKebab,
+    Flat,
+}
+
+/// Where the form of a multi-word rename for a single-word match is taken from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Resolution {
+    /// Follow the separator of the rename term
+    #[default]
+    Rename,
+    /// Follow the dominant identifier style of the match's line, then of the whole input
+    Context,
+}
+
+/// Which of the occurrences left after skipping are renamed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Occurrence {
+    #[default]
+    All,
+    /// The nth occurrence, counting from 1
+    Nth(NonZeroUsize),
+    Last,
+}
+
+/// Settings that tune how a [`Task`](super::Task) parses its terms and renders renames
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Words rendered in all caps by camelCase and PascalCase renames (`accountID`)
+    pub acronyms: Vec<String>,
+
+    /// How digits in the candidate and rename terms are grouped into words
+    pub digits: DigitPolicy,
+
+    /// Also search prose casings: `space case`, `Title Case` and `Sentence case`
+    pub prose: bool,
+
+    /// Casings searched on top of the identifier casings, e.g. `Train-Case` or `dot.case`
+    pub extra_casings: Vec<Casing>,
+
+    /// Search exactly these casings instead of the identifier, prose and extra casings
+    pub only_casings: Option<Vec<Casing>>,
+
+    /// Casings left out of the search
+    pub skip_casings: Vec<Casing>,
+
+    /// Match the flatcase and UPPERCASE forms of multi-word candidates (`myuser`, `MYUSER`)
+    /// and split flat terms into words with `dictionary`
+    pub flat: bool,
+
+    /// Word list used to split flat terms, the bundled list when `None`
+    pub dictionary: Option<Dictionary>,
+
+    /// Rendering of ambiguous lowercase matches, guessed from the rename term when `None`
+    pub lower_as: Option<LowerAs>,
+
+    /// Rendering of ambiguous UPPERCASE matches, guessed from the rename term when `None`
+    pub upper_as: Option<UpperAs>,
+
+    /// How ambiguous matches are resolved when `lower_as` or `upper_as` isn't set
+    pub resolve_ambiguity: Resolution,
+
+    /// Literal renames used for matches of a given casing instead of the derived form
+    pub overrides: Vec<(Casing, String)>,
+
+    /// Also rename case-insensitive matches with no recognised casing, mapping their
+    /// character cases onto the rename
+    pub force_mixed: bool,
+
+    /// Prefix conventions recognised as a separate segment of the terms, such as `I` in `IUser`
+    pub prefixes: Vec<String>,
+
+    /// Also match the English plural forms of the candidate, renamed to the plural of the rename
+    pub plural: bool,
+
+    /// Irregular plurals looked up before the built-in ones, as `(singular, plural)` pairs
+    pub irregulars: Vec<(String, String)>,
+
+    /// Rewrite the possessive of plural matches to suit the plural of the rename,
+    /// as in "people's" -> "customers'"
+    pub possessive: bool,
+
+    /// The candidate is a regex matched against whole identifiers in any casing, whose groups
+    /// are referenced as `{1}`, `{2}`... in the rename
+    pub regex: bool,
+
+    /// Rename even when the rename term already occurs in the input
+    pub allow_collisions: bool,
+
+    /// Language whose keywords and identifier syntax the renamed identifiers are checked against
+    pub lang: Option<Lang>,
+
+    /// What to do with renames that produce a keyword or an invalid identifier
+    pub on_invalid: OnInvalid,
+
+    /// Escape renames that produce a Rust keyword as raw identifiers (`r#type`)
+    pub raw_identifiers: bool,
+
+    /// Comment and string syntax of the input, taken from `lang` when `None`
+    pub syntax: Option<Syntax>,
+
+    /// Only rename occurrences in code, comments or strings
+    pub only: Option<Region>,
+
+    /// Only rename occurrences in the code or in the prose of a Markdown document
+    pub markdown_scope: Option<MarkdownScope>,
+
+    /// Also search prose casings, keeping their matches to the prose of a Markdown document
+    pub markdown_prose: bool,
+
+    /// Only rename occurrences in the keys of a data file of this format, or in the
+    /// `positions` selected
+    pub structured: Option<Format>,
+
+    /// Positions renamed in structured mode, keys only when empty
+    pub positions: Vec<Position>,
+
+    /// Rename lines marked with `altr:ignore-line`, `altr:ignore-next-line` or
+    /// `altr:off`/`altr:on` too
+    pub no_directives: bool,
+
+    /// Only rename occurrences on these 1-based lines, along with those in `byte_ranges`
+    pub lines: Vec<RangeInclusive<usize>>,
+
+    /// Only rename occurrences within these byte ranges, along with those on `lines`
+    pub byte_ranges: Vec<Range<usize>>,
+
+    /// Only rename occurrences between `altr:begin` and `altr:end` markers
+    pub marked: bool,
+
+    /// Conditions on the line of an occurrence and the text around it, all of which must hold
+    /// for it to be renamed
+    pub filters: Vec<Filter>,
+
+    /// Number of occurrences left alone before `occurrence` and `max_count` apply
+    pub skip: usize,
+
+    /// Which of the remaining occurrences are renamed
+    pub occurrence: Occurrence,
+
+    /// Upper bound on the number of occurrences renamed
+    pub max_count: Option<usize>,
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use crate::{token::Token, Result};
+
+/// Characters trimmed off the text around placeholders, as in `legacy_{}`
+const SEPARATORS: [char; 5] = ['_', '-', ' ', '.', '/'];
+
+#[derive(Debug)]
+enum Part {
+    Words(Token),
+    /// `{}`, the words of the whole match
+    Match,
+    /// `{1}`, the words captured by a group of a regex candidate
+    Group(usize),
+}
+
+/// A rename term wrapping the matched words, such as `{}Service`, `legacy_{}`
+/// or `account{1}Model`
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// The template in `rename`, `None` when it has no placeholder
+    pub fn parse(rename: &str) -> Result<Option<Self>> {
+        let mut parts = vec![];
+        let mut rest = rename;
+
+        while let Some((before, placeholder, after)) = next_placeholder(rest) {
+            parts.extend(words(before)?);
+            parts.push(match placeholder {
+                "" => Part::Match,
+                n => Part::Group(n.parse().map_err(|_| format!("Invalid group `{{{n}}}`"))?),
+            });
+            rest = after;
+        }
+
+        if parts.is_empty() {
+            return Ok(None);
+        }
+
+        parts.extend(words(rest)?);
+        Ok(Some(Self { parts }))
+    }
+
+    /// `rename` with one-letter words in place of the placeholders, so that its casing can be
+    /// detected
+    /// Example: "{}Service" is camelCase like "xService" and "Http{}Impl" PascalCase like "HttpXImpl"
+    pub fn sample(rename: &str) -> String {
+        let mut sample = String::new();
+        let mut rest = rename;
+
+        while let Some((before, _, after)) = next_placeholder(rest) {
+            sample.push_str(before);
+
+            let starts_word = sample.is_empty() || sample.ends_with(SEPARATORS);
+            sample.push(if starts_word { 'x' } else { 'X' });
+
+            rest = after;
+        }
+
+        sample.push_str(rest);
+        sample
+    }
+
+    /// The highest group number used by the template
+    pub fn max_group(&self) -> Option<usize> {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Group(n) => Some(*n),
+                _ => None,
+            })
+            .max()
+    }
+
+    pub fn map(self, f: impl Fn(Token) -> Token) -> Self {
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|part| match part {
+                Part::Words(token) => Part::Words(f(token)),
+                part => part,
+            })
+            .collect();
+
+        Self { parts }
+    }
+
+    /// The rename for a match made of the words in `matched`, with the words of its
+    /// regex groups in `groups`
+    pub fn expand(&self, matched: &Token, groups: &[Token]) -> Token {
+        let tokens: Vec<&Token> = self
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Words(token) => Some(token),
+                Part::Match => Some(matched),
+                Part::Group(n) => groups.get(*n),
+            })
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        Token::join(&tokens)
+    }
+}
+
+/// Splits `s` around its first `{}` or `{n}` placeholder, giving the text before it,
+/// its content and the text after it
+fn next_placeholder(s: &str) -> Option<(&str, &str, &str)> {
+    let mut offset = 0;
+
+    while let Some(start) = s[offset..].find('{').map(|i| offset + i) {
+        let end = s[start..].find('}').map(|i| start + i)?;
+        let content = &s[start + 1..end];
+
+        if content.chars().all(|c| c.is_ascii_digit()) {
+            return Some((&s[..start], content, &s[end + 1..]));
+        }
+
+        offset = start + 1;
+    }
+
+    None
+}
+
+fn words(s: &str) -> Result<Option<Part>> {
+    match s.trim_matches(SEPARATORS) {
+        "" => Ok(None),
+        s => Ok(Some(Part::Words(Token::from_str(s)?))),
+    }
+}
+
+#[cfg(test)]
+mod test_template {
+    use std::str::FromStr;
+
+    use super::Template;
+    use crate::token::Token;
+
+    #[test]
+    fn expansion() {
+        let token = Token::from_str("user").unwrap();
+
+        let expand = |rename: &str| {
+            Template::parse(rename)
+                .unwrap()
+                .unwrap()
+                .expand(&token, &[])
+                .to_snake_case()
+        };
+
+        assert_eq!(expand("{}Service"), "user_service");
+        assert_eq!(expand("legacy_{}"), "legacy_user");
+        assert_eq!(expand("Http{}Impl"), "http_user_impl");
+        assert_eq!(expand("{}"), "user");
+    }
+
+    #[test]
+    fn group_expansion() {
+        let matched = Token::from_str("userProfileDto").unwrap();
+        let groups = [
+            Token::from_str("userProfileDto").unwrap(),
+            Token::from_str("Profile").unwrap(),
+        ];
+
+        let template = Template::parse("account{1}Model").unwrap().unwrap();
+
+        assert_eq!(template.max_group(), Some(1));
+        assert_eq!(
+            template.expand(&matched, &groups).to_camel_case(),
+            "accountProfileModel"
+        );
+    }
+
+    #[test]
+    fn sample() {
+        assert_eq!(Template::sample("{}Service"), "xService");
+        assert_eq!(Template::sample("legacy_{}"), "legacy_x");
+        assert_eq!(Template::sample("Http{}Impl"), "HttpXImpl");
+        assert_eq!(Template::sample("account{1}Model"), "accountXModel");
+        assert_eq!(Template::sample("account"), "account");
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Template::parse("account").unwrap().is_none());
+        assert!(Template::parse("{x}").unwrap().is_none());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{casing::Casing, SEPARATOR};
+use std::str::FromStr;
+
+use crate::{casing::Casing, dictionary::Dictionary, plural::pluralize, SEPARATOR};
 
 #[derive(Debug)]
 pub enum TokenError {
@@ -6,7 +8,21 @@ pub enum TokenError {
     AmbiguousToUpperCase,
 }
 
-#[derive(Debug)]
+/// How digits are grouped into words when a term is parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DigitPolicy {
+    /// Keep digits where the term's casing puts them (`x_1` is `x,1` and `v2Api` is `v2,api`)
+    #[default]
+    AsWritten,
+    /// Digits belong to the preceding word (`sha_256_hash` is `sha256,hash`)
+    Attach,
+    /// Digits always form a word of their own (`sha256Hash` is `sha,256,hash`)
+    Separate,
+    /// Words only split where digits give way to a letter (`base64encode` is `base64,encode`)
+    Transition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Token(String);
 
 impl Token {
@@ -20,6 +36,13 @@ impl Token {
             Casing::Upper => self.try_to_upper_case(),
             Casing::UpperSnake => Ok(self.to_upper_snake_case()),
             Casing::UpperKebab => Ok(self.to_upper_kebab_case()),
+            Casing::Space => Ok(self.to_space_case()),
+            Casing::Title => Ok(self.to_title_case()),
+            Casing::Sentence => Ok(self.to_sentence_case()),
+            Casing::Train => Ok(self.to_train_case()),
+            Casing::Dot => Ok(self.to_dot_case()),
+            Casing::Path => Ok(self.to_path_case()),
+            Casing::Ada => Ok(self.to_ada_case()),
         }
     }
 
@@ -30,9 +53,16 @@ impl Token {
             Casing::Lower => Some(Token(input.into())),
             Casing::Kebab => Token::from_kebab_case(input),
             Casing::Snake => Token::from_snake_case(input),
-            Casing::Upper => Some(Token(input.into())),
+            Casing::Upper => Some(Token(input.to_lowercase())),
             Casing::UpperSnake => Token::from_upper_snake_case(input),
             Casing::UpperKebab => Token::from_upper_kebab_case(input),
+            Casing::Space => Token::from_space_case(input),
+            Casing::Title => Token::from_title_case(input),
+            Casing::Sentence => Token::from_sentence_case(input),
+            Casing::Train => Token::from_train_case(input),
+            Casing::Dot => Token::from_dot_case(input),
+            Casing::Path => Token::from_path_case(input),
+            Casing::Ada => Token::from_ada_case(input),
         }
         .ok_or(
             format!(
@@ -44,21 +74,11 @@ impl Token {
     }
 
     pub fn from_camel_case(input: &str) -> Option<Self> {
-        let mut result = String::new();
-
-        for (i, ch) in input.chars().enumerate() {
-            if ch.is_uppercase() {
-                if i == 0 {
-                    return None;
-                }
-                result.push(SEPARATOR);
-                result.push(ch.to_ascii_lowercase());
-            } else {
-                result.push(ch);
-            }
+        if input.chars().next().is_some_and(|c| c.is_uppercase()) {
+            return None;
         }
 
-        Some(Token(result))
+        Some(Token(split_mixed_case(input)))
     }
 
     pub fn from_snake_case(input: &str) -> Option<Self> {
@@ -67,9 +87,7 @@ impl Token {
 
     pub fn from_upper_snake_case(input: &str) -> Option<Self> {
         Some(Token(
-            input
-                .replace('_', &String::from(SEPARATOR))
-                .to_ascii_lowercase(),
+            input.replace('_', &String::from(SEPARATOR)).to_lowercase(),
         ))
     }
 
@@ -79,32 +97,52 @@ impl Token {
 
     pub fn from_upper_kebab_case(input: &str) -> Option<Self> {
         Some(Token(
-            input
-                .replace('-', &String::from(SEPARATOR))
-                .to_ascii_lowercase(),
+            input.replace('-', &String::from(SEPARATOR)).to_lowercase(),
         ))
     }
 
-    pub fn from_pascal_case(input: &str) -> Option<Self> {
-        let mut result = String::new();
+    pub fn from_space_case(input: &str) -> Option<Self> {
+        Some(Token(input.replace(' ', &String::from(SEPARATOR))))
+    }
 
-        for (i, ch) in input.chars().enumerate() {
-            if ch.is_uppercase() {
-                if i == 0 {
-                    result.push(ch.to_ascii_lowercase());
-                } else {
-                    result.push(SEPARATOR);
-                    result.push(ch.to_ascii_lowercase());
-                }
-            } else {
-                if i == 0 {
-                    return None;
-                }
-                result.push(ch);
-            }
+    pub fn from_title_case(input: &str) -> Option<Self> {
+        Some(Token(
+            input.replace(' ', &String::from(SEPARATOR)).to_lowercase(),
+        ))
+    }
+
+    pub fn from_sentence_case(input: &str) -> Option<Self> {
+        Some(Token(
+            input.replace(' ', &String::from(SEPARATOR)).to_lowercase(),
+        ))
+    }
+
+    pub fn from_train_case(input: &str) -> Option<Self> {
+        Some(Token(
+            input.replace('-', &String::from(SEPARATOR)).to_lowercase(),
+        ))
+    }
+
+    pub fn from_dot_case(input: &str) -> Option<Self> {
+        Some(Token(input.replace('.', &String::from(SEPARATOR))))
+    }
+
+    pub fn from_path_case(input: &str) -> Option<Self> {
+        Some(Token(input.replace('/', &String::from(SEPARATOR))))
+    }
+
+    pub fn from_ada_case(input: &str) -> Option<Self> {
+        Some(Token(
+            input.replace('_', &String::from(SEPARATOR)).to_lowercase(),
+        ))
+    }
+
+    pub fn from_pascal_case(input: &str) -> Option<Self> {
+        if input.chars().next().is_some_and(|c| !c.is_uppercase()) {
+            return None;
         }
 
-        Some(Token(result))
+        Some(Token(split_mixed_case(input)))
     }
 
     pub fn to_camel_case(&self) -> String {
@@ -113,12 +151,9 @@ impl Token {
             .enumerate()
             .map(|(i, part)| {
                 if i == 0 {
-                    part.to_string()
+                    part.to_lowercase()
                 } else {
-                    part.to_string()
-                        .char_indices()
-                        .map(|(i, ch)| if i == 0 { ch.to_ascii_uppercase() } else { ch })
-                        .collect()
+                    capitalize(part)
                 }
             })
             .collect::<Vec<_>>()
@@ -126,22 +161,17 @@ impl Token {
     }
 
     pub fn to_snake_case(&self) -> String {
-        self.0.replace(&String::from(SEPARATOR), "_")
+        self.0.to_lowercase().replace(&String::from(SEPARATOR), "_")
     }
 
     pub fn to_upper_snake_case(&self) -> String {
-        self.to_snake_case().to_ascii_uppercase()
+        self.to_snake_case().to_uppercase()
     }
 
     pub fn to_pascal_case(&self) -> String {
         self.0
             .split(SEPARATOR)
-            .map(|part| {
-                part.to_string()
-                    .char_indices()
-                    .map(|(i, ch)| if i == 0 { ch.to_ascii_uppercase() } else { ch })
-                    .collect::<String>()
-            })
+            .map(capitalize)
             .collect::<Vec<_>>()
             .join("")
     }
@@ -150,7 +180,7 @@ impl Token {
         if self.0.contains(SEPARATOR) {
             Err(TokenError::AmbiguousToLowerCase)
         } else {
-            Ok(self.0.clone())
+            Ok(self.0.to_lowercase())
         }
     }
 
@@ -158,19 +188,455 @@ impl Token {
         if self.0.contains(SEPARATOR) {
             Err(TokenError::AmbiguousToUpperCase)
         } else {
-            Ok(self.0.to_ascii_uppercase().clone())
+            Ok(self.0.to_uppercase())
         }
     }
 
     pub fn to_kebab_case(&self) -> String {
-        self.0.replace(&String::from(SEPARATOR), "-")
+        self.0.to_lowercase().replace(&String::from(SEPARATOR), "-")
     }
 
     pub fn to_upper_kebab_case(&self) -> String {
-        self.to_kebab_case().to_ascii_uppercase()
+        self.to_kebab_case().to_uppercase()
+    }
+
+    pub fn to_space_case(&self) -> String {
+        self.0.to_lowercase().replace(&String::from(SEPARATOR), " ")
+    }
+
+    pub fn to_title_case(&self) -> String {
+        self.0
+            .split(SEPARATOR)
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn to_sentence_case(&self) -> String {
+        self.0
+            .split(SEPARATOR)
+            .enumerate()
+            .map(|(i, part)| {
+                if i == 0 {
+                    capitalize(part)
+                } else {
+                    part.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn to_train_case(&self) -> String {
+        self.0
+            .split(SEPARATOR)
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    pub fn to_dot_case(&self) -> String {
+        self.0.to_lowercase().replace(&String::from(SEPARATOR), ".")
     }
 
-    pub fn from_str(s: &str) -> crate::Result<Self> {
+    pub fn to_path_case(&self) -> String {
+        self.0.to_lowercase().replace(&String::from(SEPARATOR), "/")
+    }
+
+    pub fn to_ada_case(&self) -> String {
+        self.0
+            .split(SEPARATOR)
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    pub fn to_flat_case(&self) -> String {
+        self.0.to_lowercase().replace(SEPARATOR, "")
+    }
+
+    pub fn to_upper_flat_case(&self) -> String {
+        self.to_flat_case().to_uppercase()
+    }
+
+    /// Renders the token by mapping the character cases of `original` onto it, for matches
+    /// with no recognised casing: `Olduser` gives `Mynewuser` for `my,new,user`.
+    /// Letters past the end of `original` follow its last letter
+    pub fn to_mixed_case(&self, original: &str) -> String {
+        let separator = ['_', '-', ' ', '.', '/']
+            .into_iter()
+            .find(|s| original.contains(*s));
+
+        let base = match separator {
+            Some(separator) => self
+                .0
+                .to_lowercase()
+                .replace(SEPARATOR, &String::from(separator)),
+            None => self.to_flat_case(),
+        };
+
+        let pattern: Vec<bool> = original
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| c.is_uppercase())
+            .collect();
+        let mut letters = 0;
+
+        base.chars()
+            .flat_map(|c| {
+                if !c.is_alphabetic() {
+                    return vec![c];
+                }
+
+                let is_upper = pattern
+                    .get(letters)
+                    .or(pattern.last())
+                    .copied()
+                    .unwrap_or(false);
+                letters += 1;
+
+                if is_upper {
+                    c.to_uppercase().collect()
+                } else {
+                    vec![c]
+                }
+            })
+            .collect()
+    }
+
+    /// The token with its last word in plural form
+    pub fn to_plural(&self, irregulars: &[(String, String)]) -> Self {
+        let mut words: Vec<_> = self.0.split(SEPARATOR).map(String::from).collect();
+
+        if let Some(last) = words.last_mut() {
+            *last = pluralize(last, irregulars);
+        }
+
+        Token(words.join(&String::from(SEPARATOR)))
+    }
+
+    /// The words of `tokens` one after another
+    pub fn join(tokens: &[&Token]) -> Self {
+        let words: Vec<&str> = tokens.iter().map(|token| token.0.as_str()).collect();
+
+        Token(words.join(&String::from(SEPARATOR)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_multi_word(&self) -> bool {
+        self.0.contains(SEPARATOR)
+    }
+
+    /// Splits a single flatcase or UPPERCASE word into the words of `dictionary`,
+    /// leaving the token untouched when it can't be split
+    pub fn segmented(self, dictionary: &Dictionary) -> Self {
+        if self.is_multi_word() {
+            return self;
+        }
+
+        match dictionary.segment(&self.0) {
+            Some(words) => Token(words.join(&String::from(SEPARATOR))),
+            None => self,
+        }
+    }
+
+    /// Regroups the digits of the token according to `policy`
+    pub fn with_digit_policy(self, policy: DigitPolicy) -> Self {
+        let mut words: Vec<String> = vec![];
+
+        for word in self.0.split(SEPARATOR) {
+            match policy {
+                DigitPolicy::AsWritten => words.push(word.to_string()),
+                DigitPolicy::Attach => match words.last_mut() {
+                    Some(prev) if word.chars().all(|c| c.is_numeric()) => prev.push_str(word),
+                    _ => words.push(word.to_string()),
+                },
+                DigitPolicy::Separate => {
+                    words.extend(split_word(word, |a, b| a.is_numeric() != b.is_numeric()))
+                }
+                DigitPolicy::Transition => {
+                    words.extend(split_word(word, |a, b| a.is_numeric() && !b.is_numeric()))
+                }
+            }
+        }
+
+        Token(words.join(&String::from(SEPARATOR)))
+    }
+
+    /// Marks every word found in `acronyms` as an acronym, so that camelCase and
+    /// PascalCase render it in all caps (`account,id` becomes `accountID`)
+    pub fn with_acronyms(self, acronyms: &[String]) -> Self {
+        let words: Vec<_> = self
+            .0
+            .split(SEPARATOR)
+            .map(|word| {
+                if acronyms
+                    .iter()
+                    .any(|a| a.to_lowercase() == word.to_lowercase())
+                {
+                    word.to_uppercase()
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect();
+
+        Token(words.join(&String::from(SEPARATOR)))
+    }
+}
+
+impl FromStr for Token {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
         Token::from_casing(&Casing::detect_casing(s)?, s)
     }
 }
+
+/// Acronyms rendered in all caps when no custom list is given
+pub const DEFAULT_ACRONYMS: &[&str] = &[
+    "api", "cpu", "css", "db", "dns", "html", "http", "https", "id", "io", "ip", "json", "jwt",
+    "sql", "ssh", "tcp", "ui", "uri", "url", "uuid", "xml",
+];
+
+/// Splits a camelCase or PascalCase identifier into words.
+///
+/// A run of capitals is kept together as an acronym, so `HTTPServer` yields
+/// `HTTP,server` and `userID` yields `user,ID`. Acronym words keep their capitals
+/// so the mixed casings render them back as written.
+fn split_mixed_case(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if i > 0 && ch.is_uppercase() {
+            let prev_is_upper = chars[i - 1].is_uppercase();
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+
+            if !prev_is_upper || next_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+
+        word.push(ch);
+    }
+    words.push(word);
+
+    words
+        .into_iter()
+        .map(|word| {
+            let is_acronym = word.chars().filter(|c| c.is_uppercase()).count() > 1
+                && !word.chars().any(|c| c.is_lowercase());
+
+            if is_acronym {
+                word
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&String::from(SEPARATOR))
+}
+
+/// Splits `word` between every pair of adjacent characters for which `is_boundary` holds
+fn split_word(word: &str, is_boundary: impl Fn(char, char) -> bool) -> Vec<String> {
+    let mut parts = vec![];
+    let mut part = String::new();
+
+    for ch in word.chars() {
+        if part
+            .chars()
+            .last()
+            .is_some_and(|prev| is_boundary(prev, ch))
+        {
+            parts.push(std::mem::take(&mut part));
+        }
+        part.push(ch);
+    }
+    parts.push(part);
+
+    parts
+}
+
+/// Uppercases the first character of `word` using full Unicode case mapping,
+/// so that characters such as `ß` expand to more than one character
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod test_token {
+    use super::*;
+
+    fn assert_conversions(input: &str, casing: Casing, expected: &str) {
+        let token = Token::from_str(input).unwrap();
+
+        assert_eq!(token.try_to_casing(&casing).unwrap(), expected);
+    }
+
+    #[test]
+    fn from_non_ascii_camel_case() {
+        assert_conversions("größeWert", Casing::UpperSnake, "GRÖSSE_WERT");
+        assert_conversions("größeWert", Casing::Pascal, "GrößeWert");
+        assert_conversions("größeWert", Casing::Kebab, "größe-wert");
+    }
+
+    #[test]
+    fn from_non_ascii_pascal_case() {
+        assert_conversions("ÜberNutzer", Casing::Camel, "überNutzer");
+        assert_conversions("ÜberNutzer", Casing::Snake, "über_nutzer");
+        assert_conversions("ÜberNutzer", Casing::UpperKebab, "ÜBER-NUTZER");
+    }
+
+    #[test]
+    fn from_non_ascii_upper_snake_case() {
+        assert_conversions("ÜBER_NUTZER", Casing::Camel, "überNutzer");
+        assert_conversions("ΌΝΟΜΑ_ΧΡΉΣΤΗ", Casing::Pascal, "ΌνομαΧρήστη");
+    }
+
+    #[test]
+    fn greek_round_trip() {
+        assert_conversions("όνομαΧρήστη", Casing::Snake, "όνομα_χρήστη");
+        assert_conversions("όνομαΧρήστη", Casing::UpperSnake, "ΌΝΟΜΑ_ΧΡΉΣΤΗ");
+        assert_conversions("όνομα_χρήστη", Casing::Camel, "όνομαΧρήστη");
+    }
+
+    #[test]
+    fn acronym_segmentation() {
+        assert_conversions("HTTPServer", Casing::Snake, "http_server");
+        assert_conversions("HTTPServer", Casing::Camel, "httpServer");
+        assert_conversions("userID", Casing::Snake, "user_id");
+        assert_conversions("userID", Casing::Pascal, "UserID");
+        assert_conversions("parseURLString", Casing::UpperKebab, "PARSE-URL-STRING");
+        assert_conversions("SHA256Hash", Casing::Snake, "sha256_hash");
+    }
+
+    #[test]
+    fn acronym_rendering() {
+        let acronyms = vec![String::from("id"), String::from("url")];
+
+        let token = Token::from_str("account_id")
+            .unwrap()
+            .with_acronyms(&acronyms);
+        assert_eq!(token.to_camel_case(), "accountID");
+        assert_eq!(token.to_snake_case(), "account_id");
+
+        let token = Token::from_str("parseUrl")
+            .unwrap()
+            .with_acronyms(&acronyms);
+        assert_eq!(token.to_camel_case(), "parseURL");
+        assert_eq!(token.to_upper_snake_case(), "PARSE_URL");
+
+        let token = Token::from_str("idValue").unwrap().with_acronyms(&acronyms);
+        assert_eq!(token.to_camel_case(), "idValue");
+        assert_eq!(token.to_pascal_case(), "IDValue");
+    }
+
+    #[test]
+    fn digit_policies() {
+        let render = |input: &str, policy: DigitPolicy| {
+            let token = Token::from_str(input).unwrap().with_digit_policy(policy);
+            (token.to_snake_case(), token.to_camel_case())
+        };
+
+        assert_eq!(
+            render("sha256Hash", DigitPolicy::AsWritten),
+            ("sha256_hash".into(), "sha256Hash".into())
+        );
+        assert_eq!(
+            render("sha_256_hash", DigitPolicy::AsWritten),
+            ("sha_256_hash".into(), "sha256Hash".into())
+        );
+        assert_eq!(
+            render("sha_256_hash", DigitPolicy::Attach),
+            ("sha256_hash".into(), "sha256Hash".into())
+        );
+        assert_eq!(
+            render("sha256Hash", DigitPolicy::Separate),
+            ("sha_256_hash".into(), "sha256Hash".into())
+        );
+        assert_eq!(
+            render("v2Api", DigitPolicy::Separate),
+            ("v_2_api".into(), "v2Api".into())
+        );
+        assert_eq!(
+            render("base64encode", DigitPolicy::Transition),
+            ("base64_encode".into(), "base64Encode".into())
+        );
+        assert_eq!(
+            render("x_1", DigitPolicy::Transition),
+            ("x_1".into(), "x1".into())
+        );
+    }
+
+    #[test]
+    fn prose_conversions() {
+        assert_conversions("supplyUser", Casing::Space, "supply user");
+        assert_conversions("supplyUser", Casing::Title, "Supply User");
+        assert_conversions("supplyUser", Casing::Sentence, "Supply user");
+        assert_conversions("Supply User", Casing::Snake, "supply_user");
+        assert_conversions("Supply user", Casing::Camel, "supplyUser");
+        assert_conversions("supply user", Casing::UpperKebab, "SUPPLY-USER");
+    }
+
+    #[test]
+    fn separated_conversions() {
+        assert_conversions("contentType", Casing::Train, "Content-Type");
+        assert_conversions("Content-Type", Casing::Snake, "content_type");
+        assert_conversions("userName", Casing::Dot, "user.name");
+        assert_conversions("com.example", Casing::Pascal, "ComExample");
+        assert_conversions("userName", Casing::Path, "user/name");
+        assert_conversions("api/users", Casing::Camel, "apiUsers");
+        assert_conversions("userName", Casing::Ada, "User_Name");
+        assert_conversions("User_Name", Casing::UpperKebab, "USER-NAME");
+    }
+
+    #[test]
+    fn flat_segmentation() {
+        let dictionary = Dictionary::bundled();
+
+        let token = Token::from_str("mynewuser").unwrap().segmented(&dictionary);
+        assert_eq!(token.to_camel_case(), "myNewUser");
+        assert_eq!(token.to_upper_flat_case(), "MYNEWUSER");
+
+        let token = Token::from_str("MYUSER").unwrap().segmented(&dictionary);
+        assert_eq!(token.to_snake_case(), "my_user");
+        assert_eq!(token.to_flat_case(), "myuser");
+    }
+
+    #[test]
+    fn mixed_case_mapping() {
+        let token = Token::from_str("myNewUser").unwrap();
+
+        assert_eq!(token.to_mixed_case("Olduser"), "Mynewuser");
+        assert_eq!(token.to_mixed_case("oLD_user"), "mY_New_user");
+        assert_eq!(token.to_mixed_case("olD"), "myNEWUSER");
+    }
+
+    #[test]
+    fn plural_conversions() {
+        let token = Token::from_str("personCount").unwrap();
+        assert_eq!(token.to_plural(&[]).to_snake_case(), "person_counts");
+
+        let token = Token::from_str("PERSON").unwrap();
+        assert_eq!(token.to_plural(&[]).to_upper_snake_case(), "PEOPLE");
+
+        let token = Token::from_str("userID").unwrap();
+        assert_eq!(token.to_plural(&[]).to_camel_case(), "userIDs");
+    }
+
+    #[test]
+    fn multi_char_expansion() {
+        assert_conversions("straße", Casing::Upper, "STRASSE");
+        assert_conversions("ßWert", Casing::Pascal, "SSWert");
+    }
+}